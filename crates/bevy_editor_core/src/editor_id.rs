@@ -0,0 +1,296 @@
+//! Stable entity ids
+//!
+//! `Entity` values change whenever an entity is recreated (scene reload, play mode
+//! restore, undoing a despawn). `EditorId` is a persistent UUID saved with the scene,
+//! `EditorIdIndex` finds the entity currently carrying it, and `StableEntity` is an
+//! entity reference that looks itself up by id.
+
+use bevy::ecs::lifecycle::HookContext;
+use bevy::ecs::world::DeferredWorld;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use uuid::Uuid;
+use crate::EditorEntity;
+
+/// Persistent id of a scene entity, saved in `.bscn` files
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[reflect(Component, Debug, PartialEq, Hash)]
+#[component(on_insert = EditorId::on_insert, on_replace = EditorId::on_replace)]
+pub struct EditorId(pub Uuid);
+
+impl EditorId {
+    pub fn new() -> Self {
+        Self(Uuid::new_v4())
+    }
+
+    fn on_insert(mut world: DeferredWorld, context: HookContext) {
+        let Some(id) = world.get::<EditorId>(context.entity).copied() else {
+            return;
+        };
+        let Some(mut index) = world.get_resource_mut::<EditorIdIndex>() else {
+            return;
+        };
+
+        // Another live entity already owns this id (e.g. the same scene spawned twice)
+        if index.entities.get(&id).is_some_and(|owner| *owner != context.entity) {
+            world.commands().entity(context.entity).try_insert(EditorId::new());
+            return;
+        }
+
+        index.entities.insert(id, context.entity);
+
+        // The entity was recreated in the same frame (play mode restore, scene reload)
+        if let Some(old) = index.despawned.remove(&id)
+            && old != context.entity
+        {
+            index.pending_remaps.push(EntityRemapped {
+                old,
+                new: context.entity,
+            });
+        }
+    }
+
+    fn on_replace(mut world: DeferredWorld, context: HookContext) {
+        let Some(id) = world.get::<EditorId>(context.entity).copied() else {
+            return;
+        };
+        let Some(mut index) = world.get_resource_mut::<EditorIdIndex>() else {
+            return;
+        };
+
+        if index.entities.get(&id) == Some(&context.entity) {
+            index.entities.remove(&id);
+            // Remember the entity so a recreation this frame can be announced
+            index.despawned.insert(id, context.entity);
+        }
+    }
+}
+
+impl Default for EditorId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Index from `EditorId` to the entity currently carrying it
+#[derive(Resource, Default)]
+pub struct EditorIdIndex {
+    entities: HashMap<EditorId, Entity>,
+    /// Ids whose entity was despawned this frame, with the entity that carried them
+    despawned: HashMap<EditorId, Entity>,
+    /// Recreations not yet announced as `EntityRemapped` messages
+    pending_remaps: Vec<EntityRemapped>,
+}
+
+impl EditorIdIndex {
+    /// Live entity with the given id
+    pub fn get(&self, id: EditorId) -> Option<Entity> {
+        self.entities.get(&id).copied()
+    }
+}
+
+/// Reference to a scene entity that stays valid when the entity is recreated
+///
+/// The entity's `EditorId` is picked up the first time it's resolved while the
+/// entity exists; after that the live entity is looked up in `EditorIdIndex`.
+/// Entities without an id fall back to the plain `Entity`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StableEntity {
+    entity: Entity,
+    id: Option<EditorId>,
+}
+
+impl StableEntity {
+    /// Reference `entity`, picking up its `EditorId` right away
+    pub fn new(world: &World, entity: Entity) -> Self {
+        Self {
+            entity,
+            id: world.get::<EditorId>(entity).copied(),
+        }
+    }
+
+    /// The entity carrying `id` right now (or the last one seen, if none does)
+    pub fn resolve(&mut self, world: &World) -> Entity {
+        match self.id {
+            Some(id) => {
+                if let Some(entity) = world.get_resource::<EditorIdIndex>().and_then(|index| index.get(id)) {
+                    self.entity = entity;
+                }
+            }
+            None => self.id = world.get::<EditorId>(self.entity).copied(),
+        }
+        self.entity
+    }
+
+    /// The last resolved entity
+    pub fn entity(&self) -> Entity {
+        self.entity
+    }
+
+    pub fn id(&self) -> Option<EditorId> {
+        self.id
+    }
+}
+
+impl From<Entity> for StableEntity {
+    fn from(entity: Entity) -> Self {
+        Self { entity, id: None }
+    }
+}
+
+/// Message sent when an entity with an `EditorId` has been recreated as a new `Entity`
+#[derive(Message, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EntityRemapped {
+    pub old: Entity,
+    pub new: Entity,
+}
+
+/// Whether `entity` is an editor entity or parented under one
+fn is_editor_owned(entity: Entity, editor_entities: &Query<(), With<EditorEntity>>, parents: &Query<&ChildOf>) -> bool {
+    editor_entities.contains(entity)
+        || parents.iter_ancestors(entity).any(|ancestor| editor_entities.contains(ancestor))
+}
+
+/// Give new scene entities an `EditorId` (entities restored from a scene keep theirs)
+///
+/// Scene entities are recognized by a `Transform` or a `Name`.
+pub(crate) fn assign_editor_id(
+    add: On<Add, (Transform, Name)>,
+    mut commands: Commands,
+    editor_entities: Query<(), With<EditorEntity>>,
+    parents: Query<&ChildOf>,
+) {
+    let entity = add.entity;
+    if is_editor_owned(entity, &editor_entities, &parents) {
+        return;
+    }
+
+    commands.entity(entity).try_insert_if_new(EditorId::new());
+}
+
+/// Take the `EditorId` back from entities that become editor-owned after spawning
+/// (marked as `EditorEntity` or parented under one later)
+pub(crate) fn strip_editor_id(
+    insert: On<Insert, (EditorEntity, ChildOf)>,
+    mut commands: Commands,
+    editor_entities: Query<(), With<EditorEntity>>,
+    parents: Query<&ChildOf>,
+    children: Query<&Children>,
+    ids: Query<(), With<EditorId>>,
+) {
+    let entity = insert.entity;
+    if !is_editor_owned(entity, &editor_entities, &parents) {
+        return;
+    }
+
+    for entity in std::iter::once(entity).chain(children.iter_descendants(entity)) {
+        if !ids.contains(entity) {
+            continue;
+        }
+
+        commands.queue(move |world: &mut World| {
+            let Some(id) = world.get_entity_mut(entity).ok().and_then(|mut entity| entity.take::<EditorId>()) else {
+                return;
+            };
+            // Removing the id isn't a despawn, there's nothing to announce
+            world.resource_mut::<EditorIdIndex>().despawned.remove(&id);
+        });
+    }
+}
+
+/// Announce entities recreated last frame and point the selection at them
+///
+/// Despawned ids are forgotten afterwards; undo commands that recreate entities
+/// later on announce those themselves.
+pub(crate) fn send_entity_remaps(
+    mut index: ResMut<EditorIdIndex>,
+    mut selection: ResMut<crate::EditorSelection>,
+    mut remaps: MessageWriter<EntityRemapped>,
+) {
+    if index.pending_remaps.is_empty() && index.despawned.is_empty() {
+        return;
+    }
+
+    index.despawned.clear();
+    for remap in std::mem::take(&mut index.pending_remaps) {
+        if selection.is_selected(remap.old) {
+            selection.remap(remap.old, remap.new);
+        }
+        remaps.write(remap);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EditorSelection;
+    use bevy::ecs::message::Messages;
+    use bevy::ecs::system::RunSystemOnce;
+
+    fn test_world() -> World {
+        let mut world = World::new();
+        world.init_resource::<EditorIdIndex>();
+        world.init_resource::<EditorSelection>();
+        world.init_resource::<Messages<EntityRemapped>>();
+        world
+    }
+
+    #[test]
+    fn index_tracks_live_entity() {
+        let mut world = test_world();
+        let id = EditorId::new();
+        let entity = world.spawn(id).id();
+        assert_eq!(world.resource::<EditorIdIndex>().get(id), Some(entity));
+
+        world.despawn(entity);
+        assert_eq!(world.resource::<EditorIdIndex>().get(id), None);
+    }
+
+    #[test]
+    fn duplicate_id_is_replaced() {
+        let mut world = test_world();
+        let id = EditorId::new();
+        let first = world.spawn(id).id();
+        let second = world.spawn(id).id();
+        world.flush();
+
+        assert_eq!(world.resource::<EditorIdIndex>().get(id), Some(first));
+        assert_ne!(world.get::<EditorId>(second), Some(&id));
+    }
+
+    #[test]
+    fn same_frame_recreation_is_announced() {
+        let mut world = test_world();
+        let id = EditorId::new();
+        let old = world.spawn(id).id();
+        world.resource_mut::<EditorSelection>().select(old);
+
+        world.despawn(old);
+        let new = world.spawn(id).id();
+        world.run_system_once(send_entity_remaps).unwrap();
+
+        assert!(world.resource::<EditorSelection>().is_selected(new));
+        assert_eq!(world.resource::<Messages<EntityRemapped>>().len(), 1);
+
+        // Despawns are only remembered for the frame they happen in
+        world.despawn(new);
+        world.run_system_once(send_entity_remaps).unwrap();
+        world.spawn(id);
+        world.run_system_once(send_entity_remaps).unwrap();
+        assert_eq!(world.resource::<Messages<EntityRemapped>>().len(), 1);
+    }
+
+    #[test]
+    fn stable_entity_follows_id() {
+        let mut world = test_world();
+        let id = EditorId::new();
+        let old = world.spawn(id).id();
+        let mut stable = StableEntity::from(old);
+        assert_eq!(stable.resolve(&world), old);
+        assert_eq!(stable.id(), Some(id));
+
+        world.despawn(old);
+        let new = world.spawn(id).id();
+        assert_eq!(stable.resolve(&world), new);
+    }
+}
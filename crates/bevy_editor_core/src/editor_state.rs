@@ -3,6 +3,7 @@
 //! Manages the overall state of the editor including project loading,
 //! editing mode, play mode, and building.
 
+use bevy::ecs::entity::EntityHashSet;
 use bevy::prelude::*;
 
 /// Top-level editor state machine
@@ -29,6 +30,8 @@ pub struct PlayModeController {
     pub state: PlayModeState,
     /// Snapshot of the game world before entering play mode
     pub game_snapshot: Option<DynamicScene>,
+    /// Every entity alive when play mode was entered, to find what was spawned while playing
+    pub entities_before_play: EntityHashSet,
 }
 
 impl Default for PlayModeController {
@@ -36,6 +39,7 @@ impl Default for PlayModeController {
         Self {
             state: PlayModeState::Stopped,
             game_snapshot: None,
+            entities_before_play: EntityHashSet::default(),
         }
     }
 }
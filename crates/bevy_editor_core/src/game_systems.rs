@@ -0,0 +1,51 @@
+//! Gating for user game systems
+//!
+//! Game logic registered through `EditorGameAppExt` only runs while the
+//! `PlayModeController` is playing or stepping, and `Time<Virtual>` is paused
+//! with it. Editor systems that animate while paused should read `Time<Real>`.
+
+use bevy::ecs::schedule::{IntoScheduleConfigs, ScheduleLabel};
+use bevy::ecs::system::ScheduleSystem;
+use bevy::prelude::*;
+use crate::{PlayModeController, PlayModeState};
+
+/// Run condition: true while the game is playing or stepping through frames
+pub fn game_running(controller: Res<PlayModeController>) -> bool {
+    controller.is_playing()
+}
+
+/// App extension for registering game logic that only runs in play mode
+pub trait EditorGameAppExt {
+    /// Add systems to `schedule` that only run while playing (or for each stepped frame)
+    fn add_game_systems<M>(
+        &mut self,
+        schedule: impl ScheduleLabel,
+        systems: impl IntoScheduleConfigs<ScheduleSystem, M>,
+    ) -> &mut Self;
+}
+
+impl EditorGameAppExt for App {
+    fn add_game_systems<M>(
+        &mut self,
+        schedule: impl ScheduleLabel,
+        systems: impl IntoScheduleConfigs<ScheduleSystem, M>,
+    ) -> &mut Self {
+        self.add_systems(schedule, systems.run_if(game_running))
+    }
+}
+
+/// Pause `Time<Virtual>` while play mode is paused so game timers and `FixedUpdate` stop too
+///
+/// Runs in `First` before the time update, so a stepped frame gets a real delta.
+pub(crate) fn sync_virtual_time(
+    controller: Res<PlayModeController>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    let should_pause = matches!(controller.state, PlayModeState::Paused);
+
+    if should_pause && !time.is_paused() {
+        time.pause();
+    } else if !should_pause && time.is_paused() {
+        time.unpause();
+    }
+}
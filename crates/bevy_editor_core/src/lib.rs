@@ -6,12 +6,22 @@
 //! - Plugin system for editor extensions
 //! - Common editor utilities and types
 
+use bevy::input::InputSystems;
+use bevy::time::TimeSystems;
 use bevy::prelude::*;
 
+pub mod editor_id;
 pub mod editor_state;
+pub mod game_systems;
+pub mod play_mode;
+pub mod preferences;
 pub mod selection;
 
+pub use editor_id::*;
 pub use editor_state::*;
+pub use game_systems::*;
+pub use play_mode::*;
+pub use preferences::*;
 pub use selection::*;
 
 /// Marker component for entities that are part of the editor infrastructure
@@ -24,6 +34,15 @@ pub struct EditorEntity;
 pub struct UiFocus {
     /// The currently focused UI element (if any)
     pub focused_entity: Option<Entity>,
+    /// Keyboard input is being typed outside the UI (e.g. numeric entry during a gizmo drag)
+    pub text_input_active: bool,
+}
+
+impl UiFocus {
+    /// Whether something is taking keyboard input, so editor shortcuts should be ignored
+    pub fn has_focus(&self) -> bool {
+        self.focused_entity.is_some() || self.text_input_active
+    }
 }
 
 /// Core editor plugin that sets up the fundamental editor infrastructure
@@ -34,7 +53,32 @@ impl Plugin for EditorCorePlugin {
         app.init_state::<EditorState>()
             .init_resource::<EditorSelection>()
             .init_resource::<UiFocus>()
-            .add_systems(Update, update_editor_state);
+            .init_resource::<EditorPreferences>()
+            .init_resource::<PlayModeController>()
+            .init_resource::<EditorIdIndex>()
+            .register_type::<EditorId>()
+            .add_message::<PlayModeAction>()
+            .add_message::<EntityRemapped>()
+            .add_message::<SelectionChangedByHistory>()
+            .add_observer(editor_id::assign_editor_id)
+            .add_observer(editor_id::strip_editor_id)
+            .add_systems(PreStartup, preferences::load_preferences_on_startup)
+            .add_systems(Update, update_editor_state)
+            .add_systems(PreUpdate, (
+                play_mode::handle_play_mode_shortcuts.after(InputSystems),
+                editor_id::send_entity_remaps,
+            ))
+            // Play mode changes land before the time update so game systems see
+            // the new state (and a matching Time<Virtual>) for the whole frame
+            .add_systems(First, (
+                play_mode::handle_play_mode_actions,
+                game_systems::sync_virtual_time,
+            ).chain().before(TimeSystems))
+            // Steps count down after the frame's game systems have run
+            .add_systems(Last, (
+                play_mode::advance_stepping,
+                preferences::save_preferences_on_change,
+            ));
     }
 }
 
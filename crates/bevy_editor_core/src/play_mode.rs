@@ -0,0 +1,282 @@
+//! Play mode controls
+//!
+//! Play snapshots every scene entity (those carrying an `EditorId`) into
+//! `PlayModeController::game_snapshot`, Stop throws away the play session and
+//! respawns that snapshot. Besides the scene, Stop only despawns `GameEntity`
+//! entities spawned while playing; anything else without an `EditorId` is left alone.
+
+use bevy::ecs::entity::{EntityHashMap, EntityHashSet};
+use bevy::ecs::system::SystemState;
+use bevy::prelude::*;
+use bevy::scene::DynamicSceneBuilder;
+use crate::{EditorId, EditorState, PlayModeController, PlayModeState, UiFocus};
+
+/// Message requesting a play mode change (from toolbar buttons or shortcuts)
+#[derive(Message, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlayModeAction {
+    /// Start playing, or resume when paused
+    Play,
+    /// Toggle pause while playing
+    Pause,
+    /// Stop playing and restore the scene from before Play
+    Stop,
+    /// Run exactly `frames` frames of game systems, then pause
+    Step { frames: u32 },
+}
+
+/// Marker for game entities outside the scene, despawned on Stop if spawned while playing
+///
+/// Entities with a `Transform` or `Name` become scene entities on their own (they get an
+/// `EditorId`), as do their children. Game bookkeeping entities without either need this
+/// marker to be cleaned up; engine and plugin entities never carry it.
+#[derive(Component, Debug, Default, Clone, Copy)]
+pub struct GameEntity;
+
+/// Collect the entities that make up the edited scene
+///
+/// Scene entities are the ones carrying an `EditorId`; editor entities and anything
+/// parented under them never get one.
+pub fn scene_entities(world: &mut World) -> Vec<Entity> {
+    let mut query = world.query_filtered::<Entity, With<EditorId>>();
+    query.iter(world).collect()
+}
+
+/// `GameEntity` entities spawned while playing, which Stop despawns along with the scene
+fn spawned_during_play(world: &mut World, before_play: &EntityHashSet) -> Vec<Entity> {
+    let mut query = world.query_filtered::<Entity, (With<GameEntity>, Without<EditorId>)>();
+    query
+        .iter(world)
+        .filter(|entity| !before_play.contains(entity))
+        .collect()
+}
+
+/// Check that every component in `snapshot` can be written back into the world
+fn check_snapshot_writable(world: &World, snapshot: &DynamicScene) -> Result<(), String> {
+    let type_registry = world.resource::<AppTypeRegistry>().read();
+
+    for entity in &snapshot.entities {
+        for component in &entity.components {
+            let Some(type_info) = component.get_represented_type_info() else {
+                return Err(format!("a component of {} has no type information", entity.entity));
+            };
+
+            let registered = type_registry
+                .get(type_info.type_id())
+                .is_some_and(|registration| registration.data::<ReflectComponent>().is_some());
+            if !registered {
+                return Err(format!("{} is not registered as a reflected component", type_info.type_path()));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Map play mode shortcuts to actions
+///
+/// Ctrl+P toggles Play/Stop, Ctrl+Shift+P pauses, Ctrl+Alt+P steps one frame.
+pub(crate) fn handle_play_mode_shortcuts(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    ui_focus: Res<UiFocus>,
+    controller: Res<PlayModeController>,
+    mut actions: MessageWriter<PlayModeAction>,
+) {
+    if ui_focus.has_focus() || !keyboard.just_pressed(KeyCode::KeyP) {
+        return;
+    }
+
+    let ctrl = keyboard.pressed(KeyCode::ControlLeft) || keyboard.pressed(KeyCode::ControlRight);
+    let shift = keyboard.pressed(KeyCode::ShiftLeft) || keyboard.pressed(KeyCode::ShiftRight);
+    let alt = keyboard.pressed(KeyCode::AltLeft) || keyboard.pressed(KeyCode::AltRight);
+
+    if !ctrl {
+        return;
+    }
+
+    if alt {
+        actions.write(PlayModeAction::Step { frames: 1 });
+    } else if shift {
+        actions.write(PlayModeAction::Pause);
+    } else if controller.is_stopped() {
+        actions.write(PlayModeAction::Play);
+    } else {
+        actions.write(PlayModeAction::Stop);
+    }
+}
+
+/// Apply pending play mode actions (exclusive system - snapshots need `&mut World`)
+pub(crate) fn handle_play_mode_actions(
+    world: &mut World,
+    actions: &mut SystemState<MessageReader<PlayModeAction>>,
+) {
+    let actions: Vec<PlayModeAction> = actions.get_mut(world).read().copied().collect();
+
+    for action in actions {
+        let state = world.resource::<PlayModeController>().state;
+
+        match (action, state) {
+            (PlayModeAction::Play, PlayModeState::Stopped) => enter_play_mode(world),
+            (PlayModeAction::Play | PlayModeAction::Pause, PlayModeState::Paused) => {
+                set_play_state(world, PlayModeState::Playing, EditorState::Playing);
+            }
+            (PlayModeAction::Pause, PlayModeState::Playing) => {
+                set_play_state(world, PlayModeState::Paused, EditorState::Paused);
+            }
+            (PlayModeAction::Step { frames }, PlayModeState::Playing | PlayModeState::Paused) if frames > 0 => {
+                set_play_state(world, PlayModeState::Stepping { frames_left: frames }, EditorState::Paused);
+            }
+            (PlayModeAction::Stop, PlayModeState::Playing | PlayModeState::Paused | PlayModeState::Stepping { .. }) => {
+                exit_play_mode(world);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn set_play_state(world: &mut World, play_state: PlayModeState, editor_state: EditorState) {
+    world.resource_mut::<PlayModeController>().state = play_state;
+    world.resource_mut::<NextState<EditorState>>().set(editor_state);
+}
+
+/// Snapshot the scene and start playing
+fn enter_play_mode(world: &mut World) {
+    let entities = scene_entities(world);
+
+    let snapshot = DynamicSceneBuilder::from_world(world)
+        .deny_all_resources()
+        .extract_entities(entities.iter().copied())
+        .build();
+
+    info!("▶ Entering play mode ({} entities snapshotted)", entities.len());
+
+    let entities_before_play = world.query::<Entity>().iter(world).collect();
+    let mut controller = world.resource_mut::<PlayModeController>();
+    controller.game_snapshot = Some(snapshot);
+    controller.entities_before_play = entities_before_play;
+    set_play_state(world, PlayModeState::Playing, EditorState::Playing);
+}
+
+/// Despawn the play session and respawn the snapshot taken on Play
+///
+/// If the snapshot can't be written back, nothing is despawned: play mode pauses with the
+/// snapshot kept, so Stop can be retried once the problem (e.g. a missing type
+/// registration) is fixed.
+fn exit_play_mode(world: &mut World) {
+    let Some(snapshot) = world.resource_mut::<PlayModeController>().game_snapshot.take() else {
+        warn!("No play mode snapshot to restore");
+        set_play_state(world, PlayModeState::Stopped, EditorState::Editing);
+        return;
+    };
+
+    // Only tear the play session down once the snapshot is known to be restorable
+    if let Err(e) = check_snapshot_writable(world, &snapshot) {
+        error!("Cannot restore the scene from before play mode ({}), staying paused", e);
+        world.resource_mut::<PlayModeController>().game_snapshot = Some(snapshot);
+        set_play_state(world, PlayModeState::Paused, EditorState::Paused);
+        return;
+    }
+
+    let entities_before_play = std::mem::take(&mut world.resource_mut::<PlayModeController>().entities_before_play);
+    let mut entities = scene_entities(world);
+    entities.extend(spawned_during_play(world, &entities_before_play));
+    for entity in entities {
+        // Children may already be gone with their parent
+        if let Ok(entity) = world.get_entity_mut(entity) {
+            entity.despawn();
+        }
+    }
+
+    // Restored entities keep their EditorId, so the selection follows them (see `EditorIdIndex`)
+    let mut entity_map = EntityHashMap::default();
+    if let Err(e) = snapshot.write_to_world(world, &mut entity_map) {
+        error!("Failed to restore scene after play mode: {}", e);
+    }
+
+    info!("■ Exited play mode ({} entities restored)", entity_map.len());
+    set_play_state(world, PlayModeState::Stopped, EditorState::Editing);
+}
+
+/// Count down single-frame steps and fall back to Paused when done
+pub(crate) fn advance_stepping(mut controller: ResMut<PlayModeController>) {
+    if let PlayModeState::Stepping { frames_left } = controller.state {
+        controller.state = if frames_left > 1 {
+            PlayModeState::Stepping { frames_left: frames_left - 1 }
+        } else {
+            PlayModeState::Paused
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EditorIdIndex;
+    use bevy::scene::DynamicEntity;
+
+    fn test_world() -> World {
+        let mut world = World::new();
+        world.init_resource::<AppTypeRegistry>();
+        {
+            let registry = world.resource::<AppTypeRegistry>();
+            let mut registry = registry.write();
+            registry.register::<Transform>();
+            registry.register::<Name>();
+            registry.register::<EditorId>();
+        }
+        world.init_resource::<EditorIdIndex>();
+        world.init_resource::<PlayModeController>();
+        world.init_resource::<NextState<EditorState>>();
+        world
+    }
+
+    #[test]
+    fn stop_restores_scene_entities() {
+        let mut world = test_world();
+        let id = EditorId::new();
+        world.spawn((Transform::from_xyz(1.0, 0.0, 0.0), id));
+        let untracked = world.spawn(Name::new("Untracked")).id();
+
+        enter_play_mode(&mut world);
+        let entity = world.resource::<EditorIdIndex>().get(id).unwrap();
+        world.get_mut::<Transform>(entity).unwrap().translation.x = 5.0;
+        world.spawn((Transform::default(), EditorId::new()));
+        let game = world.spawn(GameEntity).id();
+        let engine = world.spawn_empty().id();
+
+        exit_play_mode(&mut world);
+
+        let restored = world.resource::<EditorIdIndex>().get(id).unwrap();
+        assert_eq!(world.get::<Transform>(restored).unwrap().translation.x, 1.0);
+        assert_eq!(scene_entities(&mut world).len(), 1);
+        assert!(world.get_entity(game).is_err());
+        // Entities without an EditorId that aren't marked as game entities are never touched
+        assert!(world.get_entity(untracked).is_ok());
+        assert!(world.get_entity(engine).is_ok());
+        assert!(world.resource::<PlayModeController>().is_stopped());
+    }
+
+    #[derive(Component, Reflect)]
+    struct Unregistered;
+
+    #[test]
+    fn unrestorable_snapshot_keeps_play_entities() {
+        let mut world = test_world();
+        let entity = world.spawn((Transform::default(), EditorId::new())).id();
+
+        enter_play_mode(&mut world);
+        world.resource_mut::<PlayModeController>().game_snapshot = Some(DynamicScene {
+            resources: Vec::new(),
+            entities: vec![DynamicEntity {
+                entity,
+                components: vec![Box::new(Unregistered)],
+            }],
+        });
+
+        exit_play_mode(&mut world);
+
+        assert!(world.get_entity(entity).is_ok());
+        let controller = world.resource::<PlayModeController>();
+        assert!(controller.is_paused());
+        assert!(controller.game_snapshot.is_some());
+    }
+}
@@ -0,0 +1,150 @@
+//! Editor preferences
+//!
+//! User-level settings that persist across projects (stored as RON next to the editor).
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Default location of the preferences file
+pub const PREFERENCES_PATH: &str = "editor_preferences.ron";
+
+/// Persistent editor preferences
+#[derive(Debug, Clone, Default, PartialEq, Resource, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EditorPreferences {
+    pub undo: UndoPreferences,
+    pub snap: SnapPreferences,
+    pub camera: CameraPreferences,
+}
+
+/// Limits for the undo history
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UndoPreferences {
+    /// Maximum number of undo entries
+    pub max_entries: usize,
+    /// Approximate memory budget for the history in megabytes (`None` = unlimited)
+    pub max_memory_mb: Option<usize>,
+    /// Record selection changes as undo entries
+    pub record_selection: bool,
+}
+
+impl Default for UndoPreferences {
+    fn default() -> Self {
+        Self {
+            max_entries: 100,
+            max_memory_mb: Some(256),
+            record_selection: false,
+        }
+    }
+}
+
+impl UndoPreferences {
+    /// Memory budget in bytes
+    pub fn max_memory_bytes(&self) -> Option<usize> {
+        self.max_memory_mb.map(|mb| mb * 1024 * 1024)
+    }
+}
+
+/// Snapping for gizmo drags and inspector nudges
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SnapPreferences {
+    /// Snap to increments by default (holding Ctrl while dragging inverts this)
+    pub enabled: bool,
+    /// Translation increment (`None` = use the grid cell size)
+    pub translate_step: Option<f32>,
+    /// Rotation increment in degrees
+    pub rotate_degrees: f32,
+    /// Scale increment
+    pub scale_step: f32,
+    /// Snap dragged sprites' edges and centers to other sprites, whether or not `enabled` is
+    /// (holding Ctrl inverts this too)
+    pub to_sprites: bool,
+    /// How close (in screen pixels) sprite edges need to be to snap
+    pub sprite_threshold_px: f32,
+}
+
+impl Default for SnapPreferences {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            translate_step: None,
+            rotate_degrees: 15.0,
+            scale_step: 0.1,
+            to_sprites: true,
+            sprite_threshold_px: 8.0,
+        }
+    }
+}
+
+/// Editor camera behavior
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CameraPreferences {
+    /// How long framing the selection (F) animates the camera, in seconds (0 = instant)
+    pub frame_duration: f32,
+}
+
+impl Default for CameraPreferences {
+    fn default() -> Self {
+        Self {
+            frame_duration: 0.3,
+        }
+    }
+}
+
+/// Save preferences to a RON file
+pub fn save_preferences(preferences: &EditorPreferences, path: &Path) -> Result<(), String> {
+    let ron = ron::ser::to_string_pretty(preferences, ron::ser::PrettyConfig::default())
+        .map_err(|e| format!("Failed to serialize preferences: {}", e))?;
+
+    std::fs::write(path, ron)
+        .map_err(|e| format!("Failed to write preferences file: {}", e))
+}
+
+/// Load preferences from a RON file
+pub fn load_preferences(path: &Path) -> Result<EditorPreferences, String> {
+    let ron = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read preferences file: {}", e))?;
+
+    ron::from_str(&ron)
+        .map_err(|e| format!("Failed to deserialize preferences: {}", e))
+}
+
+/// Load saved preferences on startup (falls back to defaults)
+pub(crate) fn load_preferences_on_startup(mut preferences: ResMut<EditorPreferences>) {
+    let path = Path::new(PREFERENCES_PATH);
+    if !path.exists() {
+        return;
+    }
+
+    match load_preferences(path) {
+        Ok(loaded) => {
+            *preferences = loaded;
+            info!("Loaded editor preferences from {:?}", path);
+        }
+        Err(e) => warn!("Failed to load editor preferences, using defaults: {}", e),
+    }
+}
+
+/// Write preferences back to disk whenever they change
+pub(crate) fn save_preferences_on_change(
+    preferences: Res<EditorPreferences>,
+    mut initialized: Local<bool>,
+) {
+    // The first run only sees the startup load
+    if !*initialized {
+        *initialized = true;
+        return;
+    }
+
+    if !preferences.is_changed() {
+        return;
+    }
+
+    if let Err(e) = save_preferences(&preferences, Path::new(PREFERENCES_PATH)) {
+        warn!("Failed to save editor preferences: {}", e);
+    }
+}
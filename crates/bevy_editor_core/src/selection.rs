@@ -6,7 +6,7 @@ use bevy::prelude::*;
 use std::collections::HashSet;
 
 /// Tracks currently selected entities in the editor
-#[derive(Debug, Default, Resource)]
+#[derive(Debug, Default, Clone, PartialEq, Resource)]
 pub struct EditorSelection {
     selected: HashSet<Entity>,
     primary: Option<Entity>,
@@ -32,6 +32,22 @@ impl EditorSelection {
         }
     }
 
+    /// Add an entity to the selection and make it the primary selection
+    pub fn set_primary(&mut self, entity: Entity) {
+        self.selected.insert(entity);
+        self.primary = Some(entity);
+    }
+
+    /// Replace `old` with `new` (e.g. when an entity was recreated), keeping primary status
+    pub fn remap(&mut self, old: Entity, new: Entity) {
+        if self.selected.remove(&old) {
+            self.selected.insert(new);
+        }
+        if self.primary == Some(old) {
+            self.primary = Some(new);
+        }
+    }
+
     /// Remove an entity from the selection
     pub fn remove(&mut self, entity: Entity) {
         self.selected.remove(&entity);
@@ -80,3 +96,10 @@ impl EditorSelection {
         self.selected.is_empty()
     }
 }
+
+/// Message sent when the selection was changed by a command (undo/redo, deleting
+/// selected entities) or a scene reset, rather than by the user
+///
+/// Selection history recording skips these changes.
+#[derive(Message, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SelectionChangedByHistory;
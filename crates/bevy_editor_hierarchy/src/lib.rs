@@ -1,6 +1,7 @@
 //! Entity hierarchy tree view panel
 
 use bevy::prelude::*;
+use bevy_editor_core::EntityRemapped;
 
 pub mod tree_view;
 pub mod operations;
@@ -13,8 +14,24 @@ pub struct EditorHierarchyPlugin;
 
 impl Plugin for EditorHierarchyPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<HierarchyState>();
+        app.init_resource::<HierarchyState>()
+            .add_systems(PreUpdate, remap_hierarchy_state);
         // Note: The UI rendering happens in bevy_editor_ui crate
         // This plugin just provides the data structures and state
     }
 }
+
+/// Keep expansion and anchor state attached to recreated entities
+fn remap_hierarchy_state(
+    mut remaps: MessageReader<EntityRemapped>,
+    mut hierarchy_state: ResMut<HierarchyState>,
+) {
+    for remap in remaps.read() {
+        if hierarchy_state.expanded.remove(&remap.old) {
+            hierarchy_state.expanded.insert(remap.new);
+        }
+        if hierarchy_state.selection_anchor == Some(remap.old) {
+            hierarchy_state.selection_anchor = Some(remap.new);
+        }
+    }
+}
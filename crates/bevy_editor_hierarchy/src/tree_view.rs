@@ -21,6 +21,8 @@ pub struct HierarchyState {
     pub drop_target: Option<Entity>,
     /// Mouse position when drag started (for threshold detection)
     pub drag_start_position: Option<Vec2>,
+    /// Entity being renamed inline and the name typed so far
+    pub renaming: Option<(Entity, String)>,
 }
 
 /// Component marking a UI node that represents an entity in the hierarchy tree
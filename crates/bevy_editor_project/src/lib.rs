@@ -2,23 +2,45 @@
 
 use bevy::prelude::*;
 use bevy::scene::DynamicSceneBuilder;
+use bevy::window::PrimaryWindow;
 use bevy_editor_core::EditorEntity;
+use bevy_editor_undo::CommandHistory;
 use serde::de::DeserializeSeed;
 use std::path::PathBuf;
 
 pub mod project;
 pub mod scene_format;
+pub mod scene_template;
 
 pub use project::*;
 pub use scene_format::*;
+pub use scene_template::*;
 
 /// Current scene being edited
 #[derive(Resource, Debug, Clone)]
 pub struct CurrentScene {
     pub path: PathBuf,
+    /// Whether the undo history has moved away from the last save
     pub modified: bool,
 }
 
+impl CurrentScene {
+    /// File name for display, with an asterisk when modified
+    pub fn display_name(&self) -> String {
+        let name = self
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "Untitled".to_string());
+
+        if self.modified {
+            format!("{}*", name)
+        } else {
+            name
+        }
+    }
+}
+
 impl Default for CurrentScene {
     fn default() -> Self {
         Self {
@@ -36,10 +58,20 @@ impl Plugin for EditorProjectPlugin {
         app
             .init_resource::<CurrentProject>()
             .init_resource::<CurrentScene>()
+            .add_message::<NewScene>()
+            // Built-in scene templates
+            .register_scene_template(Empty2dTemplate)
+            .register_scene_template(Empty3dTemplate)
+            .register_scene_template(DemoSceneTemplate)
             .add_systems(Update, (
+                handle_new_scene,
                 handle_save_scene,
                 handle_load_scene,
-            ));
+            ))
+            .add_systems(PostUpdate, (
+                sync_scene_modified,
+                update_window_title,
+            ).chain());
     }
 }
 
@@ -110,6 +142,9 @@ fn handle_save_scene(world: &mut World) {
             info!("✅ Scene saved successfully to {:?}", scene_path);
             // Mark scene as unmodified (now safe to mutably borrow world)
             world.resource_mut::<CurrentScene>().modified = false;
+            if let Some(mut history) = world.get_resource_mut::<CommandHistory>() {
+                history.mark_saved();
+            }
         }
         Err(e) => {
             error!("❌ Failed to save scene: {}", e);
@@ -127,6 +162,7 @@ fn handle_load_scene(
     mut scene_spawner: ResMut<SceneSpawner>,
     type_registry: Res<AppTypeRegistry>,
     mut scenes: ResMut<Assets<DynamicScene>>,
+    mut history: ResMut<CommandHistory>,
 ) {
     // Check for Ctrl+O (Left Ctrl or Right Ctrl)
     let ctrl_pressed = keyboard.pressed(KeyCode::ControlLeft) || keyboard.pressed(KeyCode::ControlRight);
@@ -172,6 +208,10 @@ fn handle_load_scene(
                 scene_spawner.spawn_dynamic(scene_handle);
 
                 info!("Scene entities spawned");
+
+                // Old commands reference despawned entities; the loaded scene is the new save point
+                history.clear();
+                history.mark_saved();
             }
             Err(e) => {
                 error!("❌ Failed to load scene: {}", e);
@@ -179,3 +219,32 @@ fn handle_load_scene(
         }
     }
 }
+
+/// Derive `CurrentScene::modified` from the undo history's save point
+fn sync_scene_modified(
+    history: Res<CommandHistory>,
+    mut current_scene: ResMut<CurrentScene>,
+) {
+    if !history.is_changed() {
+        return;
+    }
+
+    let modified = history.is_modified();
+    if current_scene.modified != modified {
+        current_scene.modified = modified;
+    }
+}
+
+/// Show the scene name (and an asterisk while modified) in the window title
+fn update_window_title(
+    current_scene: Res<CurrentScene>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    if !current_scene.is_changed() {
+        return;
+    }
+
+    for mut window in &mut windows {
+        window.title = format!("Bevy Editor - {}", current_scene.display_name());
+    }
+}
@@ -0,0 +1,208 @@
+//! Scene templates for New Scene
+//!
+//! A new scene replaces every scene entity with whatever the chosen template spawns.
+//! The editor ships Empty 2D, Empty 3D and a sprite demo; projects can add their own
+//! with `App::register_scene_template`. The editor starts with an empty scene; write a
+//! `NewScene` message on Startup to open with a template instead.
+
+use bevy::ecs::system::SystemState;
+use bevy::picking::Pickable;
+use bevy::prelude::*;
+use bevy_editor_core::{EditorSelection, SelectionChangedByHistory, scene_entities};
+use bevy_editor_undo::CommandHistory;
+use std::path::PathBuf;
+use crate::CurrentScene;
+
+/// Trait for scene templates
+pub trait SceneTemplate: Send + Sync + 'static {
+    /// Unique identifier for this template
+    fn id(&self) -> &str;
+
+    /// Display name
+    fn name(&self) -> &str;
+
+    /// Spawn the template's entities into an empty scene
+    fn spawn(&self, world: &mut World);
+}
+
+/// Registered scene templates, keyed by `SceneTemplate::id`
+#[derive(Resource, Default)]
+pub struct SceneTemplates {
+    templates: Vec<Box<dyn SceneTemplate>>,
+}
+
+impl SceneTemplates {
+    /// Register a template (replaces an existing template with the same id)
+    pub fn register(&mut self, template: impl SceneTemplate) {
+        self.templates.retain(|existing| existing.id() != template.id());
+        self.templates.push(Box::new(template));
+    }
+
+    pub fn get(&self, id: &str) -> Option<&dyn SceneTemplate> {
+        self.templates.iter().find(|template| template.id() == id).map(|template| &**template)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn SceneTemplate> {
+        self.templates.iter().map(|template| &**template)
+    }
+}
+
+/// App extension for registering scene templates from project code
+pub trait SceneTemplateAppExt {
+    fn register_scene_template(&mut self, template: impl SceneTemplate) -> &mut Self;
+}
+
+impl SceneTemplateAppExt for App {
+    fn register_scene_template(&mut self, template: impl SceneTemplate) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_init::<SceneTemplates>()
+            .register(template);
+        self
+    }
+}
+
+/// Message requesting a new scene built from the template with the given id
+#[derive(Message, Debug, Clone, PartialEq, Eq)]
+pub struct NewScene {
+    pub template: String,
+}
+
+/// Path new scenes are saved to until they're saved elsewhere
+const UNTITLED_SCENE_PATH: &str = "scenes/untitled.bscn";
+
+/// Replace the scene with a fresh one from the requested template (exclusive system)
+pub(crate) fn handle_new_scene(
+    world: &mut World,
+    requests: &mut SystemState<MessageReader<NewScene>>,
+) {
+    // Only the last request of the frame matters
+    let Some(request) = requests.get_mut(world).read().last().cloned() else {
+        return;
+    };
+
+    if world.resource::<SceneTemplates>().get(&request.template).is_none() {
+        warn!("Unknown scene template: {}", request.template);
+        return;
+    }
+
+    world.resource_scope(|world, templates: Mut<SceneTemplates>| {
+        let Some(template) = templates.get(&request.template) else {
+            return;
+        };
+
+        info!("📄 New scene from template '{}'", template.name());
+
+        for entity in scene_entities(world) {
+            // Children may already be gone with their parent
+            if let Ok(entity) = world.get_entity_mut(entity) {
+                entity.despawn();
+            }
+        }
+        world.resource_mut::<EditorSelection>().clear();
+        world.write_message(SelectionChangedByHistory);
+
+        template.spawn(world);
+    });
+
+    *world.resource_mut::<CurrentScene>() = CurrentScene {
+        path: PathBuf::from(UNTITLED_SCENE_PATH),
+        modified: false,
+    };
+
+    // Old commands reference despawned entities; the fresh scene is the new save point
+    let mut history = world.resource_mut::<CommandHistory>();
+    history.clear();
+    history.mark_saved();
+}
+
+/// A scene with nothing in it, for 2D editing
+pub struct Empty2dTemplate;
+
+impl SceneTemplate for Empty2dTemplate {
+    fn id(&self) -> &str {
+        "empty_2d"
+    }
+
+    fn name(&self) -> &str {
+        "Empty 2D"
+    }
+
+    fn spawn(&self, _world: &mut World) {}
+}
+
+/// A 3D scene with a light and nothing else
+pub struct Empty3dTemplate;
+
+impl SceneTemplate for Empty3dTemplate {
+    fn id(&self) -> &str {
+        "empty_3d"
+    }
+
+    fn name(&self) -> &str {
+        "Empty 3D"
+    }
+
+    fn spawn(&self, world: &mut World) {
+        world.spawn((
+            DirectionalLight {
+                shadows_enabled: true,
+                ..default()
+            },
+            Transform::from_xyz(4.0, 8.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+            Name::new("Directional Light"),
+        ));
+    }
+}
+
+/// A grid of colored sprites, for trying out picking, gizmos and panel scrolling
+pub struct DemoSceneTemplate;
+
+impl SceneTemplate for DemoSceneTemplate {
+    fn id(&self) -> &str {
+        "demo_sprites"
+    }
+
+    fn name(&self) -> &str {
+        "Demo (Sprites)"
+    }
+
+    fn spawn(&self, world: &mut World) {
+        // Grid: 6 rows x 5 columns = 30 sprites
+        let colors = [
+            (Color::srgb(0.2, 0.5, 1.0), "Blue"),
+            (Color::srgb(1.0, 0.2, 0.2), "Red"),
+            (Color::srgb(1.0, 0.9, 0.2), "Yellow"),
+            (Color::srgb(0.2, 1.0, 0.2), "Green"),
+            (Color::srgb(1.0, 0.5, 0.2), "Orange"),
+            (Color::srgb(0.8, 0.2, 1.0), "Purple"),
+        ];
+
+        let spacing = 100.0;
+        let start_x = -200.0;
+        let start_y = 200.0;
+
+        for row in 0..6 {
+            for col in 0..5 {
+                let x = start_x + (col as f32 * spacing);
+                let y = start_y - (row as f32 * spacing);
+                let (color, color_name) = colors[row % colors.len()];
+
+                let size = 50.0 + (row * 5) as f32; // Vary size slightly
+
+                world.spawn((
+                    Sprite {
+                        color,
+                        custom_size: Some(Vec2::new(size, size)),
+                        ..default()
+                    },
+                    Transform::from_xyz(x, y, 0.0),
+                    Name::new(format!("{}_{}_{}", color_name, row, col)),
+                    Pickable {
+                        should_block_lower: false,
+                        is_hoverable: true,
+                    },
+                ));
+            }
+        }
+    }
+}
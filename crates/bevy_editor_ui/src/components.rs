@@ -33,3 +33,11 @@ pub struct SearchInputText;
 /// Marker component for the clear search button
 #[derive(Component)]
 pub struct ClearSearchButton;
+
+/// Marker component for the scene tab label above the viewport
+#[derive(Component)]
+pub struct SceneTabText;
+
+/// Marker component for the row the viewports (and the game view) are laid out in
+#[derive(Component)]
+pub struct ViewportArea;
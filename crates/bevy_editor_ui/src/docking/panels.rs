@@ -3,13 +3,15 @@
 //! Routes panel IDs to their actual content (hierarchy, inspector, etc.)
 
 use bevy::prelude::*;
-use crate::{SceneTreePanel, InspectorPanel};
+use bevy_editor_viewport::EditorViewport;
+use crate::{SceneTreePanel, InspectorPanel, PanelRoot, EditorPanels};
 use super::PanelContent;
 
 /// System to populate panel content areas with actual panel components
 pub fn route_panel_content(
     mut commands: Commands,
     content_query: Query<(Entity, &PanelContent), Added<PanelContent>>,
+    editor_panels: Res<EditorPanels>,
 ) {
     for (entity, content) in &content_query {
         match content.panel_id.as_str() {
@@ -22,12 +24,18 @@ pub fn route_panel_content(
                 commands.entity(entity).insert(InspectorPanel);
             }
             "Viewport" => {
-                // Viewport is transparent, no special marking needed
-                // Just ensure it doesn't block picking
+                // Each viewport panel gets its own cameras (and can be given a GridConfig override)
+                commands.entity(entity).insert(EditorViewport);
             }
             "Assets" => {
                 // TODO: Add AssetsPanel marker when implemented
             }
+            panel_id if editor_panels.get(panel_id).is_some() => {
+                // Trait-based panels build their own content under a PanelRoot
+                commands.entity(entity).insert(PanelRoot {
+                    panel_id: panel_id.to_string(),
+                });
+            }
             _ => {
                 // Unknown panel type, leave as placeholder
             }
@@ -5,8 +5,9 @@
 
 use bevy::prelude::*;
 use bevy::picking::Pickable;
-use bevy_editor_hierarchy::EntityTreeRow;
-use bevy_editor_core::EditorEntity;
+use bevy_editor_hierarchy::{EntityTreeRow, HierarchyState};
+use bevy_editor_core::{EditorEntity, EntityRemapped, UiFocus};
+use bevy_editor_undo::{DespawnEntityCommand, SpawnEntityCommand, UndoCommandsExt};
 
 /// Marker component for the context menu container
 #[derive(Component)]
@@ -199,6 +200,9 @@ pub fn handle_context_menu_actions(
     mut commands: Commands,
     interaction_query: Query<(&Interaction, &ContextMenuAction, &ChildOf), (Changed<Interaction>, With<Button>)>,
     menu_query: Query<&ContextMenu>,
+    names: Query<&Name>,
+    mut hierarchy_state: ResMut<HierarchyState>,
+    mut ui_focus: ResMut<UiFocus>,
 ) {
     for (interaction, action, child_of) in &interaction_query {
         if *interaction == Interaction::Pressed {
@@ -210,20 +214,34 @@ pub fn handle_context_menu_actions(
                 // Execute action based on type
                 match action {
                     ContextMenuAction::Delete => {
-                        // Despawn the target entity
-                        commands.entity(target_entity).despawn();
+                        // Despawn the target entity (undoable)
+                        commands.execute_undoable(DespawnEntityCommand::new(target_entity));
                     }
                     ContextMenuAction::Duplicate => {
                         // TODO: Implement duplication
                         println!("Duplicate entity {:?}", target_entity);
                     }
                     ContextMenuAction::AddChild => {
-                        // TODO: Implement add child
-                        println!("Add child to entity {:?}", target_entity);
+                        // Spawn an empty child (undoable) and show it
+                        commands.execute_undoable(
+                            SpawnEntityCommand::new((
+                                Name::new("Entity"),
+                                Transform::default(),
+                                Visibility::default(),
+                            ))
+                            .with_parent(target_entity)
+                            .with_label("Add Child"),
+                        );
+                        hierarchy_state.expanded.insert(target_entity);
                     }
                     ContextMenuAction::Rename => {
-                        // TODO: Implement rename
-                        println!("Rename entity {:?}", target_entity);
+                        // Edit the name inline in the tree row (see `handle_rename_input`)
+                        let name = names
+                            .get(target_entity)
+                            .map(|name| name.as_str().to_string())
+                            .unwrap_or_default();
+                        hierarchy_state.renaming = Some((target_entity, name));
+                        ui_focus.focused_entity = Some(target_entity);
                     }
                 }
 
@@ -252,3 +270,17 @@ pub fn close_context_menu_on_click_outside(
         }
     }
 }
+
+/// Keep open context menus pointing at their entity if it gets recreated
+pub fn remap_context_menu_targets(
+    mut remaps: MessageReader<EntityRemapped>,
+    mut menus: Query<&mut ContextMenu>,
+) {
+    for remap in remaps.read() {
+        for mut menu in &mut menus {
+            if menu.target_entity == remap.old {
+                menu.target_entity = remap.new;
+            }
+        }
+    }
+}
@@ -1,13 +1,18 @@
 //! Tree row interaction systems for selection and drag-and-drop reparenting
 //!
 //! Handles click selection (single, multi, range), drag-and-drop reparenting,
-//! and expand/collapse interactions.
+//! and expand/collapse interactions. Double-clicking a row frames it in the viewport.
 
 use bevy::prelude::*;
 use bevy_editor_core::EditorSelection;
 use bevy_editor_hierarchy::EntityTreeRow;
+use bevy_editor_undo::{ReparentCommand, UndoCommandsExt};
+use bevy_editor_viewport::FrameSelection;
 use crate::HierarchyState;
 
+/// Maximum time between two clicks on the same row for a double-click, in seconds
+const DOUBLE_CLICK_TIME: f32 = 0.4;
+
 /// Handle clicks on tree rows for selection and expand/collapse
 pub fn handle_tree_row_clicks(
     interaction_query: Query<(&Interaction, &EntityTreeRow), (Changed<Interaction>, With<Button>)>,
@@ -15,11 +20,25 @@ pub fn handle_tree_row_clicks(
     mut selection: ResMut<EditorSelection>,
     mut hierarchy_state: ResMut<HierarchyState>,
     keyboard: Res<ButtonInput<KeyCode>>,
+    time: Res<Time<Real>>,
+    mut last_click: Local<Option<(Entity, f32)>>,
+    mut frame: MessageWriter<FrameSelection>,
 ) {
     for (interaction, tree_row) in &interaction_query {
         if *interaction == Interaction::Pressed {
             let entity = tree_row.entity;
 
+            // A second click on the same row frames it (after the clicks select it)
+            let now = time.elapsed_secs();
+            let double_click = matches!(
+                *last_click,
+                Some((last_entity, last_time)) if last_entity == entity && now - last_time <= DOUBLE_CLICK_TIME
+            );
+            *last_click = if double_click { None } else { Some((entity, now)) };
+            if double_click {
+                frame.write(FrameSelection);
+            }
+
             // Check for modifier keys
             let shift = keyboard.pressed(KeyCode::ShiftLeft) || keyboard.pressed(KeyCode::ShiftRight);
             let ctrl = keyboard.pressed(KeyCode::ControlLeft) || keyboard.pressed(KeyCode::ControlRight);
@@ -179,8 +198,8 @@ pub fn handle_tree_row_drop(
             }
 
             if !is_descendant {
-                // Remove from old parent (if any) and add to new parent
-                commands.entity(target).add_children(&[dragged]);
+                // Move under the new parent (undoable)
+                commands.execute_undoable(ReparentCommand::new(dragged, Some(target)));
                 println!("Reparented {:?} under {:?}", dragged, target);
             } else {
                 println!("Cannot reparent: would create circular hierarchy");
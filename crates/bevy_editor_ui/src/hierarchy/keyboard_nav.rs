@@ -3,10 +3,12 @@
 //! Handles arrow key navigation, expand/collapse, and keyboard shortcuts.
 
 use bevy::prelude::*;
-use bevy_editor_core::EditorSelection;
+use bevy_editor_core::{EditorSelection, UiFocus};
 use bevy_editor_hierarchy::EntityTreeRow;
+use bevy_editor_undo::{DespawnEntityCommand, UndoCommandsExt};
 use crate::HierarchyState;
 use crate::SearchInputBox;
+use crate::TransformEditState;
 
 /// Handle keyboard navigation in the hierarchy tree
 pub fn handle_hierarchy_keyboard_navigation(
@@ -16,7 +18,20 @@ pub fn handle_hierarchy_keyboard_navigation(
     mut selection: ResMut<EditorSelection>,
     mut hierarchy_state: ResMut<HierarchyState>,
     search_focus_query: Query<&Interaction, With<SearchInputBox>>,
+    parents: Query<&ChildOf>,
+    transform_edit_state: Res<TransformEditState>,
+    ui_focus: Res<UiFocus>,
 ) {
+    // Arrow keys nudge the transform field being edited instead
+    if transform_edit_state.editing_field.is_some() {
+        return;
+    }
+
+    // Keys are being typed elsewhere (e.g. a value during a gizmo drag)
+    if ui_focus.has_focus() {
+        return;
+    }
+
     // Don't handle navigation if search box is focused
     for interaction in &search_focus_query {
         if matches!(interaction, Interaction::Hovered | Interaction::Pressed) {
@@ -110,10 +125,27 @@ pub fn handle_hierarchy_keyboard_navigation(
 
     // Delete: Delete selected entities
     if keyboard.just_pressed(KeyCode::Delete) {
-        for entity in selection.selected().collect::<Vec<_>>() {
-            commands.entity(entity).despawn();
+        // Children of selected entities are deleted (and restored) with their parent
+        let to_delete: Vec<Entity> = selection
+            .selected()
+            .filter(|entity| !parents.iter_ancestors(*entity).any(|ancestor| selection.is_selected(ancestor)))
+            .collect();
+
+        match to_delete.as_slice() {
+            // Nothing selected: no empty undo entry
+            [] => {}
+            // A single delete is already labelled "Delete Entity"
+            [entity] => commands.execute_undoable(DespawnEntityCommand::new(*entity)),
+            // One undo entry for the whole selection
+            _ => {
+                commands.begin_undo_transaction(format!("Delete {} Entities", to_delete.len()));
+                for &entity in &to_delete {
+                    commands.execute_undoable(DespawnEntityCommand::new(entity));
+                }
+                commands.commit_undo_transaction();
+            }
         }
-        selection.clear();
+        // The despawn commands drop deleted entities from the selection (and restore it on undo)
         hierarchy_state.selection_anchor = None;
     }
 
@@ -1,13 +1,14 @@
 //! Hierarchy panel and entity tree view
 //!
 //! Provides the scene tree view with entity selection, visibility toggles,
-//! context menus, search, keyboard navigation, and drag-and-drop reparenting.
+//! context menus, inline renaming, search, keyboard navigation, and drag-and-drop reparenting.
 
 mod context_menu;
 mod visibility;
 mod keyboard_nav;
 mod interactions;
 mod search;
+mod rename;
 mod panel;
 
 // Re-export public items
@@ -16,6 +17,7 @@ pub use context_menu::{
     handle_tree_row_right_clicks,
     handle_context_menu_actions,
     close_context_menu_on_click_outside,
+    remap_context_menu_targets,
 };
 
 pub use visibility::{
@@ -41,6 +43,8 @@ pub use search::{
     handle_clear_search_button,
 };
 
+pub use rename::handle_rename_input;
+
 pub use panel::{
     update_scene_tree_panel,
     auto_scroll_to_selection,
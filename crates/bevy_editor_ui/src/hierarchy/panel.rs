@@ -20,6 +20,7 @@ pub fn update_scene_tree_panel(
     all_entities: Query<(Entity, Option<&Name>)>,
     children_query: Query<&Children>,
     entity_row_query: Query<Entity, With<EntityTreeRow>>,
+    changed_names: Query<(), (Changed<Name>, Without<EditorEntity>)>,
     mut needs_initial_update: Local<bool>,
 ) {
     let Ok(panel_entity) = scene_tree_query.single() else {
@@ -32,8 +33,8 @@ pub fn update_scene_tree_panel(
         .map(|children| !children.iter().any(|c| entity_row_query.contains(c)))
         .unwrap_or(true);
 
-    // Only update if hierarchy state or selection changed, OR if panel is empty
-    if !is_empty && !hierarchy_state.is_changed() && !selection.is_changed() {
+    // Only update if hierarchy state, selection or names changed, OR if panel is empty
+    if !is_empty && !hierarchy_state.is_changed() && !selection.is_changed() && changed_names.is_empty() {
         return;
     }
 
@@ -171,8 +172,16 @@ pub fn update_scene_tree_panel(
                     Color::srgb(0.5, 0.5, 0.5) // Gray for hidden
                 };
 
+                // Show the typed name with a cursor while renaming inline
+                let (name, name_color) = match &hierarchy_state.renaming {
+                    Some((entity, text)) if *entity == tree_entity.entity => {
+                        (format!("{}|", text), Color::srgb(1.0, 0.9, 0.4))
+                    }
+                    _ => (tree_entity.name.clone(), name_color),
+                };
+
                 row.spawn((
-                    Text::new(&tree_entity.name),
+                    Text::new(name),
                     TextFont {
                         font_size: 13.0,
                         ..default()
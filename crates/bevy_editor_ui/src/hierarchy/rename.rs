@@ -0,0 +1,77 @@
+//! Inline renaming of entities in the hierarchy
+//!
+//! Started from the context menu; the row shows the typed name until Enter applies it
+//! (undoable) or Escape / a click elsewhere cancels.
+
+use bevy::prelude::*;
+use bevy::input::keyboard::KeyboardInput;
+use bevy::input::ButtonState;
+use bevy_editor_core::UiFocus;
+use bevy_editor_undo::{SetNameCommand, UndoCommandsExt};
+use crate::HierarchyState;
+use super::search::keycode_to_char;
+
+/// Handle keyboard input while an entity is being renamed
+pub fn handle_rename_input(
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    mut char_events: MessageReader<KeyboardInput>,
+    mut hierarchy_state: ResMut<HierarchyState>,
+    mut ui_focus: ResMut<UiFocus>,
+    entities: Query<()>,
+    mut renaming_last_frame: Local<Option<Entity>>,
+) {
+    let Some((entity, _)) = hierarchy_state.renaming else {
+        char_events.clear();
+        *renaming_last_frame = None;
+        return;
+    };
+
+    // The click that opened the rename doesn't cancel it
+    let clicked_away = *renaming_last_frame == Some(entity) && mouse_button.just_pressed(MouseButton::Left);
+    let finished = keyboard.just_pressed(KeyCode::Enter) || keyboard.just_pressed(KeyCode::Escape);
+
+    if finished || clicked_away || !entities.contains(entity) {
+        let Some((entity, name)) = hierarchy_state.renaming.take() else {
+            return;
+        };
+        if keyboard.just_pressed(KeyCode::Enter) && !name.is_empty() {
+            commands.execute_undoable(SetNameCommand::new(entity, name));
+        }
+        if ui_focus.focused_entity == Some(entity) {
+            ui_focus.focused_entity = None;
+        }
+        char_events.clear();
+        *renaming_last_frame = None;
+        return;
+    }
+
+    let shift = keyboard.pressed(KeyCode::ShiftLeft) || keyboard.pressed(KeyCode::ShiftRight);
+    let mut typed = Vec::new();
+    for event in char_events.read() {
+        if event.state == ButtonState::Pressed {
+            if event.key_code == KeyCode::Backspace {
+                typed.push(None);
+            } else if let Some(ch) = keycode_to_char(event.key_code, shift) {
+                typed.push(Some(ch));
+            }
+        }
+    }
+
+    // Only touch the state when something was typed (it rebuilds the tree)
+    if !typed.is_empty()
+        && let Some((_, name)) = &mut hierarchy_state.renaming
+    {
+        for ch in typed {
+            match ch {
+                Some(ch) => name.push(ch),
+                None => {
+                    name.pop();
+                }
+            }
+        }
+    }
+
+    *renaming_last_frame = Some(entity);
+}
@@ -88,7 +88,7 @@ pub fn handle_search_input(
 }
 
 /// Convert keycode to character (simplified implementation)
-fn keycode_to_char(key: KeyCode, shift: bool) -> Option<char> {
+pub(crate) fn keycode_to_char(key: KeyCode, shift: bool) -> Option<char> {
     match key {
         KeyCode::KeyA => Some(if shift { 'A' } else { 'a' }),
         KeyCode::KeyB => Some(if shift { 'B' } else { 'b' }),
@@ -3,6 +3,7 @@
 //! Provides eye icon buttons to show/hide entities and updates visual feedback.
 
 use bevy::prelude::*;
+use bevy_editor_undo::{ToggleVisibilityCommand, UndoCommandsExt};
 use crate::EditorIcons;
 
 /// Marker component for visibility toggle buttons in the tree
@@ -21,19 +22,13 @@ pub struct EntityNameText {
 
 /// Handle clicks on visibility toggle buttons (eye icons)
 pub fn handle_visibility_toggle_clicks(
+    mut commands: Commands,
     interaction_query: Query<(&Interaction, &VisibilityToggleButton), (Changed<Interaction>, With<Button>)>,
-    mut visibility_query: Query<&mut Visibility>,
 ) {
     for (interaction, toggle_button) in &interaction_query {
         if *interaction == Interaction::Pressed {
-            // Toggle visibility of the target entity
-            if let Ok(mut visibility) = visibility_query.get_mut(toggle_button.target_entity) {
-                *visibility = match *visibility {
-                    Visibility::Visible => Visibility::Hidden,
-                    Visibility::Hidden => Visibility::Visible,
-                    Visibility::Inherited => Visibility::Hidden,
-                };
-            }
+            // Toggle visibility of the target entity (undoable)
+            commands.execute_undoable(ToggleVisibilityCommand::new(toggle_button.target_entity));
         }
     }
 }
@@ -0,0 +1,8 @@
+//! Undo history panel
+//!
+//! Lists the entries of the `CommandHistory` and lets the user jump to any of them.
+
+mod panel;
+
+// Re-export public items
+pub use panel::{HistoryPanel, HistoryEntryButton, handle_history_entry_clicks};
@@ -0,0 +1,137 @@
+//! History panel UI generation
+//!
+//! Shows applied commands followed by undone ones, with the current position highlighted.
+
+use bevy::prelude::*;
+use bevy::picking::Pickable;
+use bevy_editor_core::EditorEntity;
+use bevy_editor_undo::CommandHistory;
+use crate::EditorPanel;
+
+/// Button for a history entry; clicking it moves the history to `position`
+#[derive(Component)]
+pub struct HistoryEntryButton {
+    /// Number of applied commands once this entry is reached (0 = initial state)
+    pub position: usize,
+}
+
+/// Dockable panel listing every entry in the `CommandHistory`
+#[derive(Default)]
+pub struct HistoryPanel {
+    /// What was last built: root entity, entry names and current position
+    displayed: Option<(Entity, Vec<String>, usize)>,
+}
+
+impl EditorPanel for HistoryPanel {
+    fn id(&self) -> &str {
+        "History"
+    }
+
+    fn title(&self) -> &str {
+        "History"
+    }
+
+    fn ui(&mut self, world: &mut World, parent: Entity) {
+        let Some(history) = world.get_resource::<CommandHistory>() else {
+            return;
+        };
+
+        let names: Vec<String> = history
+            .undo_commands()
+            .chain(history.redo_commands())
+            .map(|command| command.name().to_string())
+            .collect();
+        let state = (parent, names, history.position());
+
+        // Only rebuild when the history changed
+        if self.displayed.as_ref() == Some(&state) {
+            return;
+        }
+
+        let (_, names, position) = &state;
+        let mut parent_entity = world.entity_mut(parent);
+        parent_entity.despawn_related::<Children>();
+        parent_entity.with_children(|panel| {
+            spawn_history_row(panel, "Initial State", 0, *position);
+            for (index, name) in names.iter().enumerate() {
+                spawn_history_row(panel, name, index + 1, *position);
+            }
+        });
+
+        self.displayed = Some(state);
+    }
+
+    fn default_open(&self) -> bool {
+        true
+    }
+}
+
+/// Spawn one clickable history row
+fn spawn_history_row(
+    panel: &mut ChildSpawner,
+    name: &str,
+    row_position: usize,
+    current_position: usize,
+) {
+    let is_current = row_position == current_position;
+    // Entries past the current position have been undone
+    let is_undone = row_position > current_position;
+
+    let background = if is_current {
+        Color::srgb(0.25, 0.35, 0.55)
+    } else {
+        Color::NONE
+    };
+    let text_color = if is_undone {
+        Color::srgb(0.5, 0.5, 0.5)
+    } else {
+        Color::srgb(0.9, 0.9, 0.9)
+    };
+
+    panel
+        .spawn((
+            Button,
+            Node {
+                width: Val::Percent(100.0),
+                padding: UiRect::axes(Val::Px(6.0), Val::Px(3.0)),
+                flex_shrink: 0.0,
+                ..default()
+            },
+            BackgroundColor(background),
+            HistoryEntryButton {
+                position: row_position,
+            },
+            Pickable {
+                should_block_lower: true,
+                is_hoverable: true,
+            },
+            EditorEntity,
+        ))
+        .with_children(|row| {
+            row.spawn((
+                Text::new(name),
+                TextFont {
+                    font_size: 12.0,
+                    ..default()
+                },
+                TextColor(text_color),
+            ));
+        });
+}
+
+/// Undo or redo until the clicked history entry is reached
+pub fn handle_history_entry_clicks(
+    mut commands: Commands,
+    interaction_query: Query<(&Interaction, &HistoryEntryButton), (Changed<Interaction>, With<Button>)>,
+) {
+    for (interaction, entry) in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            let position = entry.position;
+            commands.queue(move |world: &mut World| {
+                world.resource_scope(|world, mut history: Mut<CommandHistory>| {
+                    history.jump_to(position, world);
+                });
+            });
+        }
+    }
+}
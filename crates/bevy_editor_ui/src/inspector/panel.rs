@@ -9,6 +9,7 @@ use bevy::ui::Checked;
 use bevy::ui_widgets::ValueChange;
 use bevy::ecs::spawn::Spawn;
 use bevy_editor_core::{EditorSelection, EditorEntity};
+use bevy_editor_undo::{SetReflectFieldCommand, UndoCommandsExt};
 use super::transform_editor::{TransformEditor, TransformField};
 use super::sprite_editor::{SpriteFlipXCheckbox, SpriteFlipYCheckbox, SpriteTextureButton};
 
@@ -648,12 +649,10 @@ pub fn update_inspector_panel(
                     if flip_x_checked {
                         checkbox_x.insert(Checked);
                     }
-                    checkbox_x.observe(move |trigger: On<ValueChange<bool>>,
-                                   mut sprite_query: Query<&mut Sprite>| {
-                        if let Ok(mut sprite) = sprite_query.get_mut(flip_x_entity) {
-                            sprite.flip_x = trigger.event().value;
-                            info!("Set flip_x: {}", sprite.flip_x);
-                        }
+                    checkbox_x.observe(move |trigger: On<ValueChange<bool>>, mut commands: Commands| {
+                        let value = trigger.event().value;
+                        commands.execute_undoable(SetReflectFieldCommand::new::<Sprite>(flip_x_entity, "flip_x", value));
+                        info!("Set flip_x: {}", value);
                     });
 
                     // Flip Y checkbox using feathers
@@ -677,12 +676,10 @@ pub fn update_inspector_panel(
                     if flip_y_checked {
                         checkbox_y.insert(Checked);
                     }
-                    checkbox_y.observe(move |trigger: On<ValueChange<bool>>,
-                                   mut sprite_query: Query<&mut Sprite>| {
-                        if let Ok(mut sprite) = sprite_query.get_mut(flip_y_entity) {
-                            sprite.flip_y = trigger.event().value;
-                            info!("Set flip_y: {}", sprite.flip_y);
-                        }
+                    checkbox_y.observe(move |trigger: On<ValueChange<bool>>, mut commands: Commands| {
+                        let value = trigger.event().value;
+                        commands.execute_undoable(SetReflectFieldCommand::new::<Sprite>(flip_y_entity, "flip_y", value));
+                        info!("Set flip_y: {}", value);
                     });
                 });
 
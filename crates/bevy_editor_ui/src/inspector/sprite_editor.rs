@@ -3,6 +3,7 @@
 //! Provides editable controls for Sprite properties including flip toggles and texture selection.
 
 use bevy::prelude::*;
+use bevy_editor_undo::{SetReflectFieldCommand, UndoCommandsExt};
 use rfd::FileDialog;
 use std::path::PathBuf;
 
@@ -56,15 +57,16 @@ pub fn handle_texture_button(
 
 /// Apply pending texture selection
 pub fn apply_pending_texture(
+    mut commands: Commands,
     mut pending: ResMut<PendingTextureSelection>,
-    mut sprite_query: Query<&mut Sprite>,
+    sprite_query: Query<(), With<Sprite>>,
     asset_server: Res<AssetServer>,
 ) {
     if let (Some(entity), Some(path)) = (pending.target_entity, pending.path.take()) {
-        if let Ok(mut sprite) = sprite_query.get_mut(entity) {
-            // Load the new texture
+        if sprite_query.contains(entity) {
+            // Load the new texture (undoable)
             let texture_handle: Handle<Image> = asset_server.load(path.clone());
-            sprite.image = texture_handle;
+            commands.execute_undoable(SetReflectFieldCommand::new::<Sprite>(entity, "image", texture_handle));
             info!("Applied texture: {:?}", path);
         }
         pending.target_entity = None;
@@ -3,6 +3,9 @@
 use bevy::prelude::*;
 use bevy::math::EulerRot;
 use bevy::input::keyboard::{KeyCode, KeyboardInput};
+use bevy_editor_core::EditorPreferences;
+use bevy_editor_undo::{SetTransformCommand, UndoCommandsExt};
+use bevy_editor_viewport::{GridConfig, SnapSteps, snap_value};
 
 /// Marker component for Transform property fields
 #[derive(Component, Clone, Copy, PartialEq, Debug)]
@@ -25,11 +28,16 @@ pub struct TransformEditor {
     pub field: TransformField,
 }
 
+/// Merge ids of field edit sessions start here, clear of gizmo drag ids
+const EDIT_SESSION_MERGE_ID_BASE: u64 = 1 << 63;
+
 /// Resource tracking the currently focused transform field for editing
 #[derive(Resource, Default)]
 pub struct TransformEditState {
     pub editing_field: Option<(Entity, TransformField)>,
     pub input_buffer: String,
+    /// Counts edit sessions (field clicks), so nudges within one merge into a single undo entry
+    pub session: u64,
 }
 
 /// Handle clicks on Transform editor buttons
@@ -45,6 +53,7 @@ pub fn handle_transform_editor_click(
                 let value = get_transform_field_value(transform, editor.field);
                 edit_state.editing_field = Some((editor.target_entity, editor.field));
                 edit_state.input_buffer = format!("{:.2}", value);
+                edit_state.session += 1;
             }
         }
     }
@@ -52,12 +61,70 @@ pub fn handle_transform_editor_click(
 
 /// Handle keyboard input for transform editing
 pub fn handle_transform_edit_input(
+    mut commands: Commands,
     mut edit_state: ResMut<TransformEditState>,
     keyboard: Res<ButtonInput<KeyCode>>,
     mut char_events: MessageReader<KeyboardInput>,
-    mut transforms: Query<&mut Transform>,
+    transforms: Query<&Transform>,
+    preferences: Res<EditorPreferences>,
+    grid: Res<GridConfig>,
 ) {
     if edit_state.editing_field.is_none() {
+        char_events.clear();
+        return;
+    }
+
+    // Read typed characters up front so keys handled below aren't left for the next field
+    let typed: Vec<char> = char_events
+        .read()
+        .filter_map(|event| match &event.logical_key {
+            bevy::input::keyboard::Key::Character(s) => Some(s.chars()),
+            _ => None,
+        })
+        .flatten()
+        .collect();
+
+    // Arrow Up/Down nudge the value by one snap increment
+    let nudge = if keyboard.just_pressed(KeyCode::ArrowUp) {
+        1.0
+    } else if keyboard.just_pressed(KeyCode::ArrowDown) {
+        -1.0
+    } else {
+        0.0
+    };
+    if nudge != 0.0
+        && let Some((entity, field)) = edit_state.editing_field
+        && let Ok(transform) = transforms.get(entity)
+    {
+        let steps = SnapSteps::from_preferences(&preferences.snap, &grid);
+        let step = match field {
+            TransformField::PositionX | TransformField::PositionY | TransformField::PositionZ => steps.translate,
+            // Rotation fields are edited in degrees
+            TransformField::RotationX | TransformField::RotationY | TransformField::RotationZ => {
+                Some(preferences.snap.rotate_degrees)
+            }
+            TransformField::ScaleX | TransformField::ScaleY | TransformField::ScaleZ => steps.scale,
+        };
+
+        let current = edit_state
+            .input_buffer
+            .parse::<f32>()
+            .unwrap_or_else(|_| get_transform_field_value(transform, field));
+        let mut value = current + nudge * step.unwrap_or(1.0);
+        // With snapping on (Ctrl inverts), land on a multiple of the increment
+        if SnapSteps::active(&preferences.snap, &grid, &keyboard).snaps_increments() {
+            value = snap_value(value, step);
+        }
+
+        // Nudges of one edit session merge into a single undo entry
+        let mut new_transform = *transform;
+        apply_transform_field_value(&mut new_transform, field, value);
+        commands.execute_undoable(
+            SetTransformCommand::new(entity, *transform, new_transform)
+                .with_merge_id(EDIT_SESSION_MERGE_ID_BASE + edit_state.session)
+                .with_label("Edit Transform"),
+        );
+        edit_state.input_buffer = format!("{:.2}", value);
         return;
     }
 
@@ -65,8 +132,15 @@ pub fn handle_transform_edit_input(
     if keyboard.just_pressed(KeyCode::Enter) {
         if let Some((entity, field)) = edit_state.editing_field {
             if let Ok(value) = edit_state.input_buffer.parse::<f32>() {
-                if let Ok(mut transform) = transforms.get_mut(entity) {
-                    apply_transform_field_value(&mut transform, field, value);
+                if let Ok(transform) = transforms.get(entity) {
+                    // Apply through the undo history, merging with this session's nudges
+                    let mut new_transform = *transform;
+                    apply_transform_field_value(&mut new_transform, field, value);
+                    commands.execute_undoable(
+                        SetTransformCommand::new(entity, *transform, new_transform)
+                            .with_merge_id(EDIT_SESSION_MERGE_ID_BASE + edit_state.session)
+                            .with_label("Edit Transform"),
+                    );
                 }
             }
         }
@@ -89,14 +163,10 @@ pub fn handle_transform_edit_input(
     }
 
     // Handle character input
-    for event in char_events.read() {
-        if let bevy::input::keyboard::Key::Character(ref s) = event.logical_key {
-            // Only accept numbers, decimal point, and minus sign
-            for ch in s.chars() {
-                if ch.is_numeric() || ch == '.' || ch == '-' {
-                    edit_state.input_buffer.push(ch);
-                }
-            }
+    for ch in typed {
+        // Only accept numbers, decimal point, and minus sign
+        if ch.is_numeric() || ch == '.' || ch == '-' {
+            edit_state.input_buffer.push(ch);
         }
     }
 }
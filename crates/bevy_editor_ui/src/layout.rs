@@ -6,21 +6,31 @@
 use bevy::prelude::*;
 use bevy::picking::Pickable;
 use bevy_editor_core::EditorEntity;
+use bevy_editor_project::CurrentScene;
 use crate::{
     PanelMarker, SceneTreePanel,
     SearchInputBox, SearchInputText, ClearSearchButton,
     InspectorPanel,
-    EditorIcons,
+    EditorIcons, EditorPanels, PanelRoot, SceneTabText, ViewportArea,
+    spawn_gizmo_space_button, spawn_new_scene_button, spawn_play_mode_buttons, spawn_snap_toggle_button,
+    spawn_viewport_buttons, spawn_viewport_mode_button,
+    toolbar::viewport_node,
 };
 
 /// Set up the fixed editor UI layout
 ///
 /// Creates a 4-panel layout:
-/// - Center/Left: Viewport (transparent spacer, allows 3D picking)
+/// - Center/Left: Viewports (the editor cameras' offscreen renders, below a toolbar header)
 /// - Right Top: Scene Tree panel with search
-/// - Right Bottom: Inspector panel
+/// - Right Bottom: Inspector panel, followed by registered panels that open by default (History, ...)
 /// - Bottom: Asset Browser panel (full width)
-pub fn setup_editor_ui(mut commands: Commands, icons: Res<EditorIcons>) {
+pub fn setup_editor_ui(mut commands: Commands, icons: Res<EditorIcons>, panels: Res<EditorPanels>) {
+    let default_panels: Vec<(String, String)> = panels
+        .iter()
+        .filter(|panel| panel.default_open())
+        .map(|panel| (panel.id().to_string(), panel.title().to_string()))
+        .collect();
+
     // Root container - Column layout for top content + bottom asset browser
     // CRITICAL: Pickable with should_block_lower: false allows clicks through to 3D viewport
     commands
@@ -195,6 +205,7 @@ pub fn setup_editor_ui(mut commands: Commands, icons: Res<EditorIcons>) {
                         width: Val::Auto,
                         height: Val::Percent(100.0),
                         flex_grow: 1.0,
+                        flex_direction: FlexDirection::Column,
                         ..default()
                     },
                     BackgroundColor(Color::NONE),
@@ -202,7 +213,92 @@ pub fn setup_editor_ui(mut commands: Commands, icons: Res<EditorIcons>) {
                         should_block_lower: false,
                         is_hoverable: false,  // Spacer doesn't need interaction
                     },
-                ));
+                ))
+                .with_children(|viewport| {
+                    // Viewport header: scene tab on the left, play controls on the right
+                    viewport.spawn((
+                        Node {
+                            width: Val::Percent(100.0),
+                            flex_direction: FlexDirection::Row,
+                            justify_content: JustifyContent::SpaceBetween,
+                            align_items: AlignItems::Start,
+                            ..default()
+                        },
+                        Pickable {
+                            should_block_lower: false,
+                            is_hoverable: false,
+                        },
+                    ))
+                    .with_children(|header| {
+                        // Scene tab showing the open scene (asterisk while modified)
+                        header.spawn((
+                            Node {
+                                align_self: AlignSelf::FlexStart,
+                                padding: UiRect::axes(Val::Px(12.0), Val::Px(4.0)),
+                                border: UiRect::new(Val::Px(1.0), Val::Px(1.0), Val::Px(0.0), Val::Px(1.0)),
+                                ..default()
+                            },
+                            BackgroundColor(Color::srgb(0.18, 0.18, 0.18)),
+                            BorderColor::all(Color::srgb(0.25, 0.25, 0.25)),
+                            Pickable {
+                                should_block_lower: false,
+                                is_hoverable: false,
+                            },
+                        ))
+                        .with_children(|tab| {
+                            tab.spawn((
+                                Text::new("main.bscn"),
+                                TextFont {
+                                    font_size: 12.0,
+                                    ..default()
+                                },
+                                TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                                SceneTabText,
+                            ));
+                        });
+
+                        // New Scene, 2D/3D, gizmo space and snap toggles, viewports, then Play / Pause / Stop / Step
+                        header.spawn((
+                            Node {
+                                flex_direction: FlexDirection::Row,
+                                padding: UiRect::all(Val::Px(4.0)),
+                                ..default()
+                            },
+                            Pickable {
+                                should_block_lower: false,
+                                is_hoverable: false,
+                            },
+                        ))
+                        .with_children(|toolbar| {
+                            spawn_new_scene_button(toolbar);
+                            spawn_viewport_mode_button(toolbar);
+                            spawn_gizmo_space_button(toolbar);
+                            spawn_snap_toggle_button(toolbar);
+                            spawn_viewport_buttons(toolbar);
+                            spawn_play_mode_buttons(toolbar);
+                        });
+                    });
+
+                    // The viewports side by side, each showing its cameras' render target
+                    // (see `EditorViewport`); the toolbar adds more and the game view
+                    viewport.spawn((
+                        Node {
+                            width: Val::Percent(100.0),
+                            flex_grow: 1.0,
+                            flex_direction: FlexDirection::Row,
+                            column_gap: Val::Px(2.0),
+                            ..default()
+                        },
+                        Pickable {
+                            should_block_lower: false,
+                            is_hoverable: false,
+                        },
+                        ViewportArea,
+                    ))
+                    .with_children(|area| {
+                        area.spawn(viewport_node());
+                    });
+                });
 
                 // Inspector panel (right side)
                 content_row.spawn((
@@ -259,6 +355,45 @@ pub fn setup_editor_ui(mut commands: Commands, icons: Res<EditorIcons>) {
                                 EditorEntity,
                             ))
                             .observe(crate::scroll::on_scroll_handler);
+
+                            // Registered panels that open by default get a section below the Inspector
+                            for (panel_id, title) in &default_panels {
+                                panel.spawn((
+                                    Text::new(title.clone()),
+                                    TextFont {
+                                        font_size: 14.0,
+                                        ..default()
+                                    },
+                                    TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                                    Node {
+                                        margin: UiRect::vertical(Val::Px(8.0)),
+                                        ..default()
+                                    },
+                                ));
+
+                                // Content area filled by the panel's `EditorPanel::ui`
+                                panel.spawn((
+                                    Node {
+                                        width: Val::Percent(100.0),
+                                        height: Val::Px(180.0),
+                                        flex_shrink: 0.0,
+                                        flex_direction: FlexDirection::Column,
+                                        overflow: Overflow::scroll_y(),
+                                        ..default()
+                                    },
+                                    ScrollPosition(Vec2::ZERO),
+                                    BackgroundColor(Color::srgb(0.12, 0.12, 0.12)),
+                                    Pickable {
+                                        should_block_lower: false,
+                                        is_hoverable: true,  // Needs to be hoverable for scroll detection
+                                    },
+                                    PanelRoot {
+                                        panel_id: panel_id.clone(),
+                                    },
+                                    EditorEntity,
+                                ))
+                                .observe(crate::scroll::on_scroll_handler);
+                            }
                         });
             });
 
@@ -299,3 +434,15 @@ pub fn setup_editor_ui(mut commands: Commands, icons: Res<EditorIcons>) {
             });
         });
 }
+
+/// Keep the scene tab label in sync with the current scene (name + modified asterisk)
+pub fn update_scene_tab_text(
+    current_scene: Res<CurrentScene>,
+    mut tab_texts: Query<(&mut Text, Ref<SceneTabText>)>,
+) {
+    for (mut text, marker) in &mut tab_texts {
+        if current_scene.is_changed() || marker.is_added() {
+            text.0 = current_scene.display_name();
+        }
+    }
+}
@@ -10,10 +10,12 @@ pub mod panel;
 pub mod widgets;
 pub mod inspector;
 pub mod hierarchy;
+pub mod history;
 pub mod components;
 pub mod layout;
 pub mod scroll;
 pub mod icons;
+pub mod toolbar;
 
 pub use docking::*;
 pub use panel::*;
@@ -23,9 +25,16 @@ pub use hierarchy::{
     ContextMenu, ContextMenuAction,
     VisibilityToggleButton, EntityNameText,
 };
+pub use history::{HistoryPanel, HistoryEntryButton};
 pub use components::*;
-pub use layout::setup_editor_ui;
+pub use layout::{setup_editor_ui, update_scene_tab_text};
 pub use icons::{EditorIcons, load_editor_icons};
+pub use toolbar::{
+    AddViewportButton, GameViewButton, GizmoSpaceButton, NewSceneButton, NewSceneMenu, PlayModeButton,
+    RemoveViewportButton, SceneTemplateButton, SnapToggleButton, ViewportModeButton,
+    spawn_gizmo_space_button, spawn_new_scene_button, spawn_play_mode_buttons, spawn_snap_toggle_button,
+    spawn_viewport_buttons, spawn_viewport_mode_button,
+};
 
 
 /// Plugin for the native bevy_ui editor UI system
@@ -40,6 +49,9 @@ impl Plugin for EditorUiPlugin {
             .init_resource::<DividerDragState>()
             .init_resource::<TransformEditState>()
             .init_resource::<HierarchyState>()
+            .init_resource::<EditorPanels>()
+            // Built-in trait-based panels
+            .register_editor_panel(HistoryPanel::default())
             // Startup systems
             .add_systems(Startup,
                 load_editor_icons,  // Load icon assets first
@@ -95,8 +107,10 @@ impl Plugin for EditorUiPlugin {
                 hierarchy::handle_tree_row_drag_over,
                 hierarchy::handle_tree_row_drop,
                 hierarchy::handle_search_input,
+                hierarchy::handle_rename_input.after(hierarchy::handle_context_menu_actions),
                 hierarchy::handle_clear_search_button,
                 hierarchy::close_context_menu_on_click_outside,
+                hierarchy::remap_context_menu_targets,
                 hierarchy::update_scene_tree_panel,
                 hierarchy::update_tree_row_visibility_appearance,
                 hierarchy::auto_scroll_to_selection,
@@ -110,6 +124,29 @@ impl Plugin for EditorUiPlugin {
                 inspector::handle_texture_button,
                 inspector::apply_pending_texture,
             ))
+            // Trait-based panels (History, ...)
+            .add_systems(Update, (
+                update_editor_panels,
+                history::handle_history_entry_clicks,
+            ))
+            .add_systems(Update, (
+                update_scene_tab_text,
+                toolbar::handle_play_mode_buttons,
+                toolbar::update_play_mode_buttons,
+                toolbar::handle_viewport_mode_button,
+                toolbar::update_viewport_mode_button,
+                toolbar::handle_gizmo_space_button,
+                toolbar::update_gizmo_space_button,
+                toolbar::handle_snap_toggle_button,
+                toolbar::update_snap_toggle_button,
+                toolbar::handle_add_viewport_button,
+                toolbar::handle_remove_viewport_button,
+                toolbar::handle_game_view_button,
+                toolbar::update_game_view_button,
+                toolbar::handle_new_scene_button,
+                toolbar::handle_scene_template_buttons,
+                toolbar::close_new_scene_menu_on_click_outside,
+            ))
             // Scroll handling
             .add_systems(Update, scroll::send_scroll_events)
             .add_observer(scroll::on_scroll_handler)
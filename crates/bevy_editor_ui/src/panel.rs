@@ -29,3 +29,63 @@ pub trait EditorPanel: Send + Sync + 'static {
 pub struct PanelRoot {
     pub panel_id: String,
 }
+
+/// Registered panel implementations, keyed by `EditorPanel::id`
+#[derive(Resource, Default)]
+pub struct EditorPanels {
+    panels: Vec<Box<dyn EditorPanel>>,
+}
+
+impl EditorPanels {
+    /// Register a panel (replaces an existing panel with the same id)
+    pub fn register(&mut self, panel: impl EditorPanel) {
+        self.panels.retain(|existing| existing.id() != panel.id());
+        self.panels.push(Box::new(panel));
+    }
+
+    pub fn get(&self, id: &str) -> Option<&dyn EditorPanel> {
+        self.panels.iter().find(|panel| panel.id() == id).map(|panel| &**panel)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn EditorPanel> {
+        self.panels.iter().map(|panel| &**panel)
+    }
+}
+
+/// App extension for registering editor panels from other crates
+pub trait EditorPanelAppExt {
+    fn register_editor_panel(&mut self, panel: impl EditorPanel) -> &mut Self;
+}
+
+impl EditorPanelAppExt for App {
+    fn register_editor_panel(&mut self, panel: impl EditorPanel) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_init::<EditorPanels>()
+            .register(panel);
+        self
+    }
+}
+
+/// Let every registered panel update the UI under its `PanelRoot`
+///
+/// Panels are called every frame and are responsible for skipping
+/// rebuilds when nothing they display has changed.
+pub fn update_editor_panels(world: &mut World) {
+    let mut roots = world.query::<(Entity, &PanelRoot)>();
+    let roots: Vec<(Entity, String)> = roots
+        .iter(world)
+        .map(|(entity, root)| (entity, root.panel_id.clone()))
+        .collect();
+
+    if roots.is_empty() {
+        return;
+    }
+
+    world.resource_scope(|world, mut panels: Mut<EditorPanels>| {
+        for (entity, panel_id) in roots {
+            if let Some(panel) = panels.panels.iter_mut().find(|panel| panel.id() == panel_id) {
+                panel.ui(world, entity);
+            }
+        }
+    });
+}
@@ -0,0 +1,647 @@
+//! Viewport toolbar with the New Scene menu, play mode controls, the 2D/3D switch,
+//! the gizmo space indicator, the snap toggle and the buttons adding and removing
+//! viewports and the game view
+
+use bevy::prelude::*;
+use bevy::picking::Pickable;
+use bevy_editor_core::{EditorEntity, EditorPreferences, PlayModeAction, PlayModeController, PlayModeState, UiFocus};
+use bevy_editor_project::{NewScene, SceneTemplates};
+use bevy_editor_viewport::{EditorViewport, GameViewport, GizmoDragState, GizmoSpace, ViewportMode};
+use crate::ViewportArea;
+
+/// Toolbar button sending a play mode action when clicked
+#[derive(Component)]
+pub struct PlayModeButton {
+    pub action: PlayModeAction,
+}
+
+/// Toolbar button showing the viewport mode, switching between 2D and 3D when clicked
+#[derive(Component)]
+pub struct ViewportModeButton;
+
+/// Text of the viewport mode button
+#[derive(Component)]
+pub struct ViewportModeText;
+
+/// Toolbar button showing the current gizmo space, toggling it when clicked
+#[derive(Component)]
+pub struct GizmoSpaceButton;
+
+/// Text of the gizmo space button
+#[derive(Component)]
+pub struct GizmoSpaceText;
+
+/// Toolbar button toggling snapping (highlighted while snapping is enabled)
+#[derive(Component)]
+pub struct SnapToggleButton;
+
+/// Toolbar button adding a viewport next to the existing ones
+#[derive(Component)]
+pub struct AddViewportButton;
+
+/// Toolbar button removing the last viewport (the first one always stays)
+#[derive(Component)]
+pub struct RemoveViewportButton;
+
+/// Toolbar button showing or hiding the game view (highlighted while it's shown)
+#[derive(Component)]
+pub struct GameViewButton;
+
+/// Toolbar button opening the New Scene template menu
+#[derive(Component)]
+pub struct NewSceneButton;
+
+/// Dropdown listing the registered scene templates
+#[derive(Component)]
+pub struct NewSceneMenu;
+
+/// Entry of the New Scene menu creating a scene from a template
+#[derive(Component)]
+pub struct SceneTemplateButton {
+    /// Id of the template (see `SceneTemplate::id`)
+    pub template: String,
+}
+
+const BUTTON_COLOR: Color = Color::srgb(0.2, 0.2, 0.2);
+const ACTIVE_BUTTON_COLOR: Color = Color::srgb(0.25, 0.35, 0.55);
+
+/// Spawn the Play / Pause / Stop / Step buttons
+pub fn spawn_play_mode_buttons(parent: &mut ChildSpawnerCommands) {
+    let buttons = [
+        (PlayModeAction::Play, "Play"),
+        (PlayModeAction::Pause, "Pause"),
+        (PlayModeAction::Stop, "Stop"),
+        (PlayModeAction::Step { frames: 1 }, "Step"),
+    ];
+
+    for (action, label) in buttons {
+        parent
+            .spawn((
+                Button,
+                Node {
+                    padding: UiRect::axes(Val::Px(10.0), Val::Px(4.0)),
+                    margin: UiRect::left(Val::Px(4.0)),
+                    border: UiRect::all(Val::Px(1.0)),
+                    ..default()
+                },
+                BackgroundColor(BUTTON_COLOR),
+                BorderColor::all(Color::srgb(0.3, 0.3, 0.3)),
+                PlayModeButton { action },
+                Pickable {
+                    should_block_lower: true,
+                    is_hoverable: true,
+                },
+                EditorEntity,
+            ))
+            .with_children(|button| {
+                button.spawn((
+                    Text::new(label),
+                    TextFont {
+                        font_size: 12.0,
+                        ..default()
+                    },
+                    TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                ));
+            });
+    }
+}
+
+/// Send the play mode action of clicked toolbar buttons
+pub fn handle_play_mode_buttons(
+    interaction_query: Query<(&Interaction, &PlayModeButton), (Changed<Interaction>, With<Button>)>,
+    mut actions: MessageWriter<PlayModeAction>,
+) {
+    for (interaction, button) in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            actions.write(button.action);
+        }
+    }
+}
+
+/// Highlight the button matching the current play mode state
+pub fn update_play_mode_buttons(
+    controller: Res<PlayModeController>,
+    mut buttons: Query<(&PlayModeButton, &mut BackgroundColor)>,
+) {
+    if !controller.is_changed() {
+        return;
+    }
+
+    for (button, mut background) in &mut buttons {
+        let active = matches!(
+            (button.action, controller.state),
+            (PlayModeAction::Play, PlayModeState::Playing)
+                | (PlayModeAction::Pause, PlayModeState::Paused)
+                | (PlayModeAction::Step { .. }, PlayModeState::Stepping { .. })
+        );
+
+        background.0 = if active { ACTIVE_BUTTON_COLOR } else { BUTTON_COLOR };
+    }
+}
+
+/// Label for the viewport mode button
+fn viewport_mode_label(mode: ViewportMode) -> &'static str {
+    match mode {
+        ViewportMode::TwoD => "2D",
+        ViewportMode::ThreeD => "3D",
+    }
+}
+
+/// Spawn the 2D / 3D viewport mode switch
+pub fn spawn_viewport_mode_button(parent: &mut ChildSpawnerCommands) {
+    parent
+        .spawn((
+            Button,
+            Node {
+                padding: UiRect::axes(Val::Px(10.0), Val::Px(4.0)),
+                margin: UiRect::right(Val::Px(8.0)),
+                border: UiRect::all(Val::Px(1.0)),
+                ..default()
+            },
+            BackgroundColor(BUTTON_COLOR),
+            BorderColor::all(Color::srgb(0.3, 0.3, 0.3)),
+            ViewportModeButton,
+            Pickable {
+                should_block_lower: true,
+                is_hoverable: true,
+            },
+            EditorEntity,
+        ))
+        .with_children(|button| {
+            button.spawn((
+                Text::new(viewport_mode_label(ViewportMode::default())),
+                TextFont {
+                    font_size: 12.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                ViewportModeText,
+            ));
+        });
+}
+
+/// Switch between 2D and 3D when the viewport mode button is clicked (not mid-drag)
+pub fn handle_viewport_mode_button(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<ViewportModeButton>)>,
+    drag_state: Res<GizmoDragState>,
+    mut viewport_mode: ResMut<ViewportMode>,
+) {
+    for interaction in &interaction_query {
+        if *interaction == Interaction::Pressed && !drag_state.is_dragging {
+            *viewport_mode = viewport_mode.toggled();
+        }
+    }
+}
+
+/// Keep the viewport mode button label in sync
+pub fn update_viewport_mode_button(
+    viewport_mode: Res<ViewportMode>,
+    mut texts: Query<(&mut Text, Ref<ViewportModeText>)>,
+) {
+    for (mut text, marker) in &mut texts {
+        if viewport_mode.is_changed() || marker.is_added() {
+            text.0 = viewport_mode_label(*viewport_mode).to_string();
+        }
+    }
+}
+
+/// Label for the gizmo space button
+fn gizmo_space_label(space: GizmoSpace) -> &'static str {
+    match space {
+        GizmoSpace::Local => "Local",
+        GizmoSpace::World => "World",
+    }
+}
+
+/// Spawn the Local / World gizmo space toggle
+pub fn spawn_gizmo_space_button(parent: &mut ChildSpawnerCommands) {
+    parent
+        .spawn((
+            Button,
+            Node {
+                padding: UiRect::axes(Val::Px(10.0), Val::Px(4.0)),
+                margin: UiRect::right(Val::Px(8.0)),
+                border: UiRect::all(Val::Px(1.0)),
+                ..default()
+            },
+            BackgroundColor(BUTTON_COLOR),
+            BorderColor::all(Color::srgb(0.3, 0.3, 0.3)),
+            GizmoSpaceButton,
+            Pickable {
+                should_block_lower: true,
+                is_hoverable: true,
+            },
+            EditorEntity,
+        ))
+        .with_children(|button| {
+            button.spawn((
+                Text::new(gizmo_space_label(GizmoSpace::default())),
+                TextFont {
+                    font_size: 12.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                GizmoSpaceText,
+            ));
+        });
+}
+
+/// Toggle the gizmo space when its toolbar button is clicked
+pub fn handle_gizmo_space_button(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<GizmoSpaceButton>)>,
+    mut gizmo_space: ResMut<GizmoSpace>,
+) {
+    for interaction in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            *gizmo_space = gizmo_space.toggled();
+        }
+    }
+}
+
+/// Keep the gizmo space button label in sync (it can also be toggled with T)
+pub fn update_gizmo_space_button(
+    gizmo_space: Res<GizmoSpace>,
+    mut texts: Query<(&mut Text, Ref<GizmoSpaceText>)>,
+) {
+    for (mut text, marker) in &mut texts {
+        if gizmo_space.is_changed() || marker.is_added() {
+            text.0 = gizmo_space_label(*gizmo_space).to_string();
+        }
+    }
+}
+
+/// Spawn the snap toggle
+pub fn spawn_snap_toggle_button(parent: &mut ChildSpawnerCommands) {
+    parent
+        .spawn((
+            Button,
+            Node {
+                padding: UiRect::axes(Val::Px(10.0), Val::Px(4.0)),
+                margin: UiRect::right(Val::Px(8.0)),
+                border: UiRect::all(Val::Px(1.0)),
+                ..default()
+            },
+            BackgroundColor(BUTTON_COLOR),
+            BorderColor::all(Color::srgb(0.3, 0.3, 0.3)),
+            SnapToggleButton,
+            Pickable {
+                should_block_lower: true,
+                is_hoverable: true,
+            },
+            EditorEntity,
+        ))
+        .with_children(|button| {
+            button.spawn((
+                Text::new("Snap"),
+                TextFont {
+                    font_size: 12.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.9, 0.9, 0.9)),
+            ));
+        });
+}
+
+/// Toggle increment snapping when the snap button is clicked (saved with the editor preferences)
+///
+/// Sprite edge snapping has its own preference (`SnapPreferences::to_sprites`).
+pub fn handle_snap_toggle_button(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<SnapToggleButton>)>,
+    mut preferences: ResMut<EditorPreferences>,
+) {
+    for interaction in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            preferences.snap.enabled = !preferences.snap.enabled;
+        }
+    }
+}
+
+/// Highlight the snap button while snapping is enabled
+pub fn update_snap_toggle_button(
+    preferences: Res<EditorPreferences>,
+    mut buttons: Query<(&mut BackgroundColor, Ref<SnapToggleButton>)>,
+) {
+    for (mut background, marker) in &mut buttons {
+        if preferences.is_changed() || marker.is_added() {
+            background.0 = if preferences.snap.enabled { ACTIVE_BUTTON_COLOR } else { BUTTON_COLOR };
+        }
+    }
+}
+
+/// A viewport node for the `ViewportArea` (its cameras are set up by the viewport crate)
+pub(crate) fn viewport_node() -> impl Bundle {
+    (
+        Node {
+            flex_grow: 1.0,
+            flex_basis: Val::Px(0.0),
+            height: Val::Percent(100.0),
+            ..default()
+        },
+        EditorViewport,
+        EditorEntity,
+    )
+}
+
+/// Spawn a toolbar button with a text label
+fn spawn_toolbar_button(parent: &mut ChildSpawnerCommands, label: &str, marker: impl Bundle) {
+    parent
+        .spawn((
+            Button,
+            Node {
+                padding: UiRect::axes(Val::Px(10.0), Val::Px(4.0)),
+                margin: UiRect::right(Val::Px(4.0)),
+                border: UiRect::all(Val::Px(1.0)),
+                ..default()
+            },
+            BackgroundColor(BUTTON_COLOR),
+            BorderColor::all(Color::srgb(0.3, 0.3, 0.3)),
+            marker,
+            Pickable {
+                should_block_lower: true,
+                is_hoverable: true,
+            },
+            EditorEntity,
+        ))
+        .with_children(|button| {
+            button.spawn((
+                Text::new(label),
+                TextFont {
+                    font_size: 12.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.9, 0.9, 0.9)),
+            ));
+        });
+}
+
+/// Spawn the add / remove viewport buttons and the game view toggle
+pub fn spawn_viewport_buttons(parent: &mut ChildSpawnerCommands) {
+    spawn_toolbar_button(parent, "+ View", AddViewportButton);
+    spawn_toolbar_button(parent, "- View", RemoveViewportButton);
+    spawn_toolbar_button(parent, "Game", GameViewButton);
+}
+
+/// Add a viewport when the add button is clicked
+pub fn handle_add_viewport_button(
+    mut commands: Commands,
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<AddViewportButton>)>,
+    area_query: Query<Entity, With<ViewportArea>>,
+) {
+    let Ok(area) = area_query.single() else {
+        return;
+    };
+
+    for interaction in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            commands.entity(area).with_children(|area| {
+                area.spawn(viewport_node());
+            });
+        }
+    }
+}
+
+/// Remove the last viewport when the remove button is clicked, keeping at least one
+pub fn handle_remove_viewport_button(
+    mut commands: Commands,
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<RemoveViewportButton>)>,
+    area_query: Query<&Children, With<ViewportArea>>,
+    viewports: Query<(), With<EditorViewport>>,
+) {
+    let Ok(children) = area_query.single() else {
+        return;
+    };
+
+    for interaction in &interaction_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        let mut area_viewports = children.iter().filter(|child| viewports.contains(*child));
+        if let Some(last) = area_viewports.next_back()
+            && area_viewports.next().is_some()
+        {
+            // Its cameras and grid go with it
+            commands.entity(last).despawn();
+        }
+    }
+}
+
+/// Show or hide the game view when its button is clicked
+pub fn handle_game_view_button(
+    mut commands: Commands,
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<GameViewButton>)>,
+    area_query: Query<Entity, With<ViewportArea>>,
+    game_viewports: Query<Entity, With<GameViewport>>,
+) {
+    let Ok(area) = area_query.single() else {
+        return;
+    };
+
+    for interaction in &interaction_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        if game_viewports.is_empty() {
+            commands.entity(area).with_children(|area| {
+                area.spawn((
+                    Node {
+                        flex_grow: 1.0,
+                        flex_basis: Val::Px(0.0),
+                        height: Val::Percent(100.0),
+                        ..default()
+                    },
+                    // Shown until the scene has a camera
+                    BackgroundColor(Color::srgb(0.05, 0.05, 0.05)),
+                    // Only previews, input stays with the editor viewports
+                    Pickable::IGNORE,
+                    GameViewport,
+                    EditorEntity,
+                ));
+            });
+        } else {
+            for game_viewport in &game_viewports {
+                commands.entity(game_viewport).despawn();
+            }
+        }
+    }
+}
+
+/// Highlight the game view button while the game view is shown
+pub fn update_game_view_button(
+    game_viewports: Query<(), With<GameViewport>>,
+    mut buttons: Query<&mut BackgroundColor, With<GameViewButton>>,
+) {
+    let color = if game_viewports.is_empty() { BUTTON_COLOR } else { ACTIVE_BUTTON_COLOR };
+    for mut background in &mut buttons {
+        background.set_if_neq(BackgroundColor(color));
+    }
+}
+
+/// Spawn the New Scene button
+pub fn spawn_new_scene_button(parent: &mut ChildSpawnerCommands) {
+    parent
+        .spawn((
+            Button,
+            Node {
+                padding: UiRect::axes(Val::Px(10.0), Val::Px(4.0)),
+                margin: UiRect::right(Val::Px(8.0)),
+                border: UiRect::all(Val::Px(1.0)),
+                ..default()
+            },
+            BackgroundColor(BUTTON_COLOR),
+            BorderColor::all(Color::srgb(0.3, 0.3, 0.3)),
+            NewSceneButton,
+            Pickable {
+                should_block_lower: true,
+                is_hoverable: true,
+            },
+            EditorEntity,
+        ))
+        .with_children(|button| {
+            button.spawn((
+                Text::new("New Scene"),
+                TextFont {
+                    font_size: 12.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.9, 0.9, 0.9)),
+            ));
+        });
+}
+
+/// Open (or close) the template menu from the New Scene button or Ctrl+N
+pub fn handle_new_scene_button(
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    ui_focus: Res<UiFocus>,
+    templates: Res<SceneTemplates>,
+    button_query: Query<(Ref<Interaction>, &ComputedNode, &UiGlobalTransform), With<NewSceneButton>>,
+    menu_query: Query<Entity, With<NewSceneMenu>>,
+) {
+    let Ok((interaction, node, transform)) = button_query.single() else {
+        return;
+    };
+
+    let clicked = interaction.is_changed() && *interaction == Interaction::Pressed;
+    let ctrl = keyboard.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    let shortcut = ctrl && keyboard.just_pressed(KeyCode::KeyN) && !ui_focus.has_focus();
+    if !clicked && !shortcut {
+        return;
+    }
+
+    if !menu_query.is_empty() {
+        for menu in &menu_query {
+            commands.entity(menu).despawn();
+        }
+        return;
+    }
+
+    // Drop the menu down from the button's bottom-left corner (logical pixels)
+    let scale = node.inverse_scale_factor();
+    let size = node.size() * scale;
+    let position = transform.translation * scale + Vec2::new(-size.x / 2.0, size.y / 2.0 + 2.0);
+
+    spawn_new_scene_menu(&mut commands, &templates, position);
+}
+
+/// Spawn the template menu at a position
+fn spawn_new_scene_menu(commands: &mut Commands, templates: &SceneTemplates, position: Vec2) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(position.x),
+                top: Val::Px(position.y),
+                width: Val::Px(150.0),
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(4.0)),
+                border: UiRect::all(Val::Px(1.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgb(0.2, 0.2, 0.2)),
+            BorderColor::all(Color::srgb(0.4, 0.4, 0.4)),
+            GlobalZIndex(100),
+            Interaction::default(),
+            NewSceneMenu,
+            EditorEntity,
+            Pickable {
+                should_block_lower: true,
+                is_hoverable: true,
+            },
+        ))
+        .with_children(|menu| {
+            for template in templates.iter() {
+                menu.spawn((
+                    Button,
+                    Node {
+                        width: Val::Percent(100.0),
+                        height: Val::Px(24.0),
+                        padding: UiRect::all(Val::Px(8.0)),
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgb(0.2, 0.2, 0.2)),
+                    SceneTemplateButton {
+                        template: template.id().to_string(),
+                    },
+                    Pickable {
+                        should_block_lower: true,
+                        is_hoverable: true,
+                    },
+                ))
+                .with_children(|button| {
+                    button.spawn((
+                        Text::new(template.name()),
+                        TextFont {
+                            font_size: 12.0,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                    ));
+                });
+            }
+        });
+}
+
+/// Create a new scene from the clicked template and close the menu
+pub fn handle_scene_template_buttons(
+    mut commands: Commands,
+    interaction_query: Query<(&Interaction, &SceneTemplateButton), (Changed<Interaction>, With<Button>)>,
+    menu_query: Query<Entity, With<NewSceneMenu>>,
+    mut new_scene: MessageWriter<NewScene>,
+) {
+    for (interaction, button) in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            new_scene.write(NewScene {
+                template: button.template.clone(),
+            });
+
+            for menu in &menu_query {
+                commands.entity(menu).despawn();
+            }
+        }
+    }
+}
+
+/// Close the template menu when clicking outside of it (the New Scene button toggles it itself)
+pub fn close_new_scene_menu_on_click_outside(
+    mut commands: Commands,
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    menu_query: Query<(Entity, &Interaction), With<NewSceneMenu>>,
+    button_query: Query<&Interaction, With<NewSceneButton>>,
+) {
+    if !mouse_button.just_pressed(MouseButton::Left) {
+        return;
+    }
+
+    if button_query.iter().any(|interaction| *interaction != Interaction::None) {
+        return;
+    }
+
+    for (menu, interaction) in &menu_query {
+        if *interaction == Interaction::None {
+            commands.entity(menu).despawn();
+        }
+    }
+}
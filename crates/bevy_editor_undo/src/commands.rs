@@ -1,6 +1,1007 @@
 //! Built-in command implementations
+//!
+//! Reversible versions of the operations the editor performs on the scene:
+//! spawning, despawning, editing transforms and names, reparenting,
+//! toggling visibility, setting reflected component fields and changing the
+//! selection, plus `CompositeCommand` for grouping several of them into one undo entry.
 
 use super::*;
+use bevy::ecs::entity::EntityHashMap;
+use bevy::reflect::{GetPath, ReflectRef, TypePath};
+use bevy::platform::time::Instant;
+use bevy::scene::DynamicSceneBuilder;
+use bevy_editor_core::{EditorSelection, EntityRemapped, SelectionChangedByHistory, StableEntity};
+use std::any::Any;
+use std::time::Duration;
 
-// Placeholder for command implementations
-// Will be expanded as we implement specific operations
+/// Selection changes closer together than this merge into one undo entry
+const SELECTION_MERGE_WINDOW: Duration = Duration::from_millis(500);
+
+/// Approximate size of a reflected value in bytes, including the heap data it owns
+///
+/// `size_of_val` on a boxed value only sees the inline part, so lists, maps,
+/// sets and strings are walked to count their contents as well.
+pub fn reflect_memory_usage(value: &dyn PartialReflect) -> usize {
+    match value.reflect_ref() {
+        ReflectRef::Struct(value) => value.iter_fields().map(reflect_memory_usage).sum(),
+        ReflectRef::TupleStruct(value) => value.iter_fields().map(reflect_memory_usage).sum(),
+        ReflectRef::Tuple(value) => value.iter_fields().map(reflect_memory_usage).sum(),
+        ReflectRef::Enum(value) => {
+            std::mem::size_of::<usize>() + value.iter_fields().map(|field| reflect_memory_usage(field.value())).sum::<usize>()
+        }
+        ReflectRef::List(value) => {
+            std::mem::size_of::<Vec<u8>>() + value.iter().map(reflect_memory_usage).sum::<usize>()
+        }
+        ReflectRef::Array(value) => value.iter().map(reflect_memory_usage).sum(),
+        ReflectRef::Map(value) => {
+            std::mem::size_of::<Vec<u8>>()
+                + value
+                    .iter()
+                    .map(|(key, value)| reflect_memory_usage(key) + reflect_memory_usage(value))
+                    .sum::<usize>()
+        }
+        ReflectRef::Set(value) => {
+            std::mem::size_of::<Vec<u8>>() + value.iter().map(reflect_memory_usage).sum::<usize>()
+        }
+        // Opaque values: count the text of strings, the inline size of everything else
+        _ => {
+            if let Some(string) = value.try_downcast_ref::<String>() {
+                std::mem::size_of::<String>() + string.capacity()
+            } else if let Some(name) = value.try_downcast_ref::<Name>() {
+                std::mem::size_of::<Name>() + name.as_str().len()
+            } else {
+                std::mem::size_of_val(value)
+            }
+        }
+    }
+}
+
+/// Snapshot of an entity and all of its descendants
+///
+/// Only reflected components registered in the `AppTypeRegistry` are captured.
+pub struct EntitySnapshot {
+    scene: DynamicScene,
+    root: Entity,
+    parent: Option<StableEntity>,
+}
+
+impl EntitySnapshot {
+    /// Capture an entity and its descendants from the world
+    pub fn capture(world: &World, root: Entity) -> Self {
+        let mut entities = Vec::new();
+        collect_descendants(world, root, &mut entities);
+
+        let scene = DynamicSceneBuilder::from_world(world)
+            .deny_all_resources()
+            .extract_entities(entities.into_iter())
+            .build();
+
+        Self {
+            scene,
+            root,
+            parent: world
+                .get::<ChildOf>(root)
+                .map(|child_of| StableEntity::new(world, child_of.parent())),
+        }
+    }
+
+    /// Write the snapshot back into the world, returning the new root entity
+    pub fn restore(&self, world: &mut World) -> Option<Entity> {
+        self.restore_mapped(world).map(|(root, _)| root)
+    }
+
+    /// Like `restore`, but also returns the old -> new mapping for every restored entity
+    pub fn restore_mapped(&self, world: &mut World) -> Option<(Entity, EntityHashMap<Entity>)> {
+        let mut entity_map = EntityHashMap::default();
+
+        // Keep the root attached to its original parent instead of a fresh entity
+        let parent = self.parent.map(|mut parent| {
+            let old_parent = parent.entity();
+            let parent = parent.resolve(world);
+            entity_map.insert(old_parent, parent);
+            parent
+        });
+
+        if let Err(e) = self.scene.write_to_world(world, &mut entity_map) {
+            error!("Failed to restore entity snapshot: {}", e);
+            return None;
+        }
+
+        let root = *entity_map.get(&self.root)?;
+
+        // Let entity-keyed editor state (hierarchy expansion, open menus) follow the new entities
+        for old in self.entities() {
+            if let Some(new) = entity_map.get(&old).copied()
+                && new != old
+            {
+                world.write_message(EntityRemapped { old, new });
+            }
+        }
+
+        // Scene writes skip relationship hooks, so re-link the root to update the parent's Children
+        if let Some(parent) = parent {
+            if world.get_entity(parent).is_ok() {
+                world.entity_mut(root).insert(ChildOf(parent));
+            } else {
+                world.entity_mut(root).remove::<ChildOf>();
+            }
+        }
+
+        Some((root, entity_map))
+    }
+
+    /// Entities captured in this snapshot (with their original ids)
+    pub fn entities(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.scene.entities.iter().map(|entity| entity.entity)
+    }
+
+    /// Approximate size of the captured components in bytes
+    pub fn memory_usage(&self) -> usize {
+        let components: usize = self
+            .scene
+            .entities
+            .iter()
+            .flat_map(|entity| entity.components.iter())
+            .map(|component| reflect_memory_usage(&**component))
+            .sum();
+
+        std::mem::size_of::<Self>() + components
+    }
+}
+
+/// Collect an entity and all of its descendants (depth-first)
+fn collect_descendants(world: &World, entity: Entity, result: &mut Vec<Entity>) {
+    result.push(entity);
+    if let Some(children) = world.get::<Children>(entity) {
+        for child in children.iter() {
+            collect_descendants(world, child, result);
+        }
+    }
+}
+
+/// Spawn a new entity from a bundle
+pub struct SpawnEntityCommand<B: Bundle + Clone> {
+    bundle: B,
+    parent: Option<StableEntity>,
+    entity: Option<StableEntity>,
+    snapshot: Option<EntitySnapshot>,
+    label: String,
+}
+
+impl<B: Bundle + Clone> SpawnEntityCommand<B> {
+    pub fn new(bundle: B) -> Self {
+        Self {
+            bundle,
+            parent: None,
+            entity: None,
+            snapshot: None,
+            label: "Spawn Entity".to_string(),
+        }
+    }
+
+    /// Spawn the entity as a child of `parent`
+    pub fn with_parent(mut self, parent: Entity) -> Self {
+        self.parent = Some(parent.into());
+        self
+    }
+
+    /// Override the name shown in the undo history
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = label.into();
+        self
+    }
+
+    /// The spawned entity (after the command has been executed)
+    pub fn entity(&self) -> Option<Entity> {
+        self.entity.as_ref().map(StableEntity::entity)
+    }
+}
+
+impl<B: Bundle + Clone> Command for SpawnEntityCommand<B> {
+    fn execute(&mut self, world: &mut World) {
+        let parent = self.parent.as_mut().map(|parent| parent.resolve(world));
+        let mut entity = world.spawn(self.bundle.clone());
+        if let Some(parent) = parent {
+            entity.insert(ChildOf(parent));
+        }
+        let entity = entity.id();
+        // Commands queued by observers (e.g. the `EditorId`) are applied on flush
+        world.flush();
+        self.entity = Some(StableEntity::new(world, entity));
+    }
+
+    fn undo(&mut self, world: &mut World) {
+        let Some(mut entity) = self.entity.take() else {
+            return;
+        };
+        let entity = entity.resolve(world);
+
+        if world.get_entity(entity).is_ok() {
+            // Keep anything added to the entity after it was spawned for redo
+            self.snapshot = Some(EntitySnapshot::capture(world, entity));
+            world.despawn(entity);
+        }
+    }
+
+    fn redo(&mut self, world: &mut World) {
+        match self.snapshot.take() {
+            Some(snapshot) => {
+                self.entity = snapshot.restore(world).map(|entity| StableEntity::new(world, entity));
+            }
+            None => self.execute(world),
+        }
+    }
+
+    fn name(&self) -> &str {
+        &self.label
+    }
+
+    fn memory_usage(&self) -> usize {
+        std::mem::size_of::<Self>() + self.snapshot.as_ref().map_or(0, EntitySnapshot::memory_usage)
+    }
+}
+
+/// Despawn an entity and its children, restoring the full hierarchy on undo
+///
+/// The selection from before the delete is restored on undo.
+pub struct DespawnEntityCommand {
+    entity: StableEntity,
+    snapshot: Option<EntitySnapshot>,
+    /// Selection before the delete
+    selection: Option<StableSelection>,
+}
+
+impl DespawnEntityCommand {
+    pub fn new(entity: Entity) -> Self {
+        Self {
+            entity: entity.into(),
+            snapshot: None,
+            selection: None,
+        }
+    }
+
+    /// The entity currently tracked by this command (changes when undo respawns it)
+    pub fn entity(&self) -> Entity {
+        self.entity.entity()
+    }
+}
+
+impl Command for DespawnEntityCommand {
+    fn execute(&mut self, world: &mut World) {
+        let entity = self.entity.resolve(world);
+        if world.get_entity(entity).is_err() {
+            warn!("Cannot despawn {:?}: entity does not exist", entity);
+            return;
+        }
+
+        let snapshot = EntitySnapshot::capture(world, entity);
+
+        // Drop despawned entities from the selection, remembering the whole selection for undo
+        self.selection = world
+            .get_resource::<EditorSelection>()
+            .map(|selection| StableSelection::capture(world, selection));
+        let mut deselected = false;
+        if let Some(mut selection) = world.get_resource_mut::<EditorSelection>() {
+            for entity in snapshot.entities() {
+                if selection.is_selected(entity) {
+                    selection.remove(entity);
+                    deselected = true;
+                }
+            }
+        }
+        if deselected {
+            world.write_message(SelectionChangedByHistory);
+        }
+
+        self.snapshot = Some(snapshot);
+        world.despawn(entity);
+    }
+
+    fn undo(&mut self, world: &mut World) {
+        let Some(entity) = self.snapshot.take().and_then(|snapshot| snapshot.restore(world)) else {
+            return;
+        };
+
+        self.entity = StableEntity::new(world, entity);
+
+        // Restored entities keep their EditorId, so the old selection resolves to them
+        let Some(selection) = &mut self.selection else {
+            return;
+        };
+        let restored = selection.resolve(world);
+        if world.get_resource::<EditorSelection>() != Some(&restored) {
+            world.insert_resource(restored);
+            world.write_message(SelectionChangedByHistory);
+        }
+    }
+
+    fn name(&self) -> &str {
+        "Delete Entity"
+    }
+
+    fn memory_usage(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.snapshot.as_ref().map_or(0, EntitySnapshot::memory_usage)
+            + self.selection.as_ref().map_or(0, StableSelection::memory_usage)
+    }
+}
+
+/// Set an entity's Transform
+///
+/// Commands sharing a merge id (e.g. every frame of one gizmo drag) collapse
+/// into a single history entry going from the first `old` to the last `new`.
+pub struct SetTransformCommand {
+    entity: StableEntity,
+    old: Transform,
+    new: Transform,
+    merge_id: Option<u64>,
+    label: String,
+}
+
+impl SetTransformCommand {
+    pub fn new(entity: Entity, old: Transform, new: Transform) -> Self {
+        Self {
+            entity: entity.into(),
+            old,
+            new,
+            merge_id: None,
+            label: "Set Transform".to_string(),
+        }
+    }
+
+    /// Allow merging with later commands for the same entity and id
+    pub fn with_merge_id(mut self, merge_id: u64) -> Self {
+        self.merge_id = Some(merge_id);
+        self
+    }
+
+    /// Override the name shown in the undo history
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = label.into();
+        self
+    }
+
+    pub fn entity(&self) -> Entity {
+        self.entity.entity()
+    }
+}
+
+impl Command for SetTransformCommand {
+    fn execute(&mut self, world: &mut World) {
+        let entity = self.entity.resolve(world);
+        if let Some(mut transform) = world.get_mut::<Transform>(entity) {
+            *transform = self.new;
+        }
+    }
+
+    fn undo(&mut self, world: &mut World) {
+        let entity = self.entity.resolve(world);
+        if let Some(mut transform) = world.get_mut::<Transform>(entity) {
+            *transform = self.old;
+        }
+    }
+
+    fn name(&self) -> &str {
+        &self.label
+    }
+
+    fn can_merge(&self, other: &dyn Command) -> bool {
+        let Some(other) = (other as &dyn Any).downcast_ref::<Self>() else {
+            return false;
+        };
+
+        self.merge_id.is_some() && self.merge_id == other.merge_id && self.entity() == other.entity()
+    }
+
+    fn merge(&mut self, other: Box<dyn Command>) {
+        let other: Box<dyn Any> = other;
+        if let Ok(other) = other.downcast::<Self>() {
+            self.new = other.new;
+        }
+    }
+}
+
+/// Set (or add) an entity's Name
+pub struct SetNameCommand {
+    entity: StableEntity,
+    old: Option<Name>,
+    new: Name,
+}
+
+impl SetNameCommand {
+    pub fn new(entity: Entity, new: impl Into<Name>) -> Self {
+        Self {
+            entity: entity.into(),
+            old: None,
+            new: new.into(),
+        }
+    }
+}
+
+impl Command for SetNameCommand {
+    fn execute(&mut self, world: &mut World) {
+        let entity = self.entity.resolve(world);
+        let Ok(mut entity) = world.get_entity_mut(entity) else {
+            return;
+        };
+
+        self.old = entity.get::<Name>().cloned();
+        entity.insert(self.new.clone());
+    }
+
+    fn undo(&mut self, world: &mut World) {
+        let entity = self.entity.resolve(world);
+        let Ok(mut entity) = world.get_entity_mut(entity) else {
+            return;
+        };
+
+        match self.old.clone() {
+            Some(old) => {
+                entity.insert(old);
+            }
+            None => {
+                entity.remove::<Name>();
+            }
+        }
+    }
+
+    fn name(&self) -> &str {
+        "Rename Entity"
+    }
+
+    fn memory_usage(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.new.as_str().len()
+            + self.old.as_ref().map_or(0, |old| old.as_str().len())
+    }
+}
+
+/// Move an entity under a new parent (or to the root with `None`)
+pub struct ReparentCommand {
+    entity: StableEntity,
+    old_parent: Option<StableEntity>,
+    new_parent: Option<StableEntity>,
+}
+
+impl ReparentCommand {
+    pub fn new(entity: Entity, new_parent: Option<Entity>) -> Self {
+        Self {
+            entity: entity.into(),
+            old_parent: None,
+            new_parent: new_parent.map(StableEntity::from),
+        }
+    }
+
+    fn set_parent(world: &mut World, entity: Entity, parent: Option<&mut StableEntity>) {
+        let parent = parent.map(|parent| parent.resolve(world));
+        let Ok(mut entity) = world.get_entity_mut(entity) else {
+            return;
+        };
+
+        match parent {
+            Some(parent) => {
+                entity.insert(ChildOf(parent));
+            }
+            None => {
+                entity.remove::<ChildOf>();
+            }
+        }
+    }
+}
+
+impl Command for ReparentCommand {
+    fn execute(&mut self, world: &mut World) {
+        let entity = self.entity.resolve(world);
+        self.old_parent = world
+            .get::<ChildOf>(entity)
+            .map(|child_of| StableEntity::new(world, child_of.parent()));
+        Self::set_parent(world, entity, self.new_parent.as_mut());
+    }
+
+    fn undo(&mut self, world: &mut World) {
+        let entity = self.entity.resolve(world);
+        Self::set_parent(world, entity, self.old_parent.as_mut());
+    }
+
+    fn name(&self) -> &str {
+        "Reparent Entity"
+    }
+}
+
+/// Toggle an entity between visible and hidden
+pub struct ToggleVisibilityCommand {
+    entity: StableEntity,
+    old: Option<Visibility>,
+}
+
+impl ToggleVisibilityCommand {
+    pub fn new(entity: Entity) -> Self {
+        Self {
+            entity: entity.into(),
+            old: None,
+        }
+    }
+}
+
+impl Command for ToggleVisibilityCommand {
+    fn execute(&mut self, world: &mut World) {
+        let entity = self.entity.resolve(world);
+        if let Some(mut visibility) = world.get_mut::<Visibility>(entity) {
+            self.old = Some(*visibility);
+            *visibility = match *visibility {
+                Visibility::Visible => Visibility::Hidden,
+                Visibility::Hidden => Visibility::Visible,
+                Visibility::Inherited => Visibility::Hidden,
+            };
+        }
+    }
+
+    fn undo(&mut self, world: &mut World) {
+        let entity = self.entity.resolve(world);
+        if let (Some(old), Some(mut visibility)) = (self.old, world.get_mut::<Visibility>(entity)) {
+            *visibility = old;
+        }
+    }
+
+    fn name(&self) -> &str {
+        "Toggle Visibility"
+    }
+}
+
+/// Set a single field of a reflected component (e.g. `Sprite` + `"color"`)
+pub struct SetReflectFieldCommand {
+    entity: StableEntity,
+    component_type_path: String,
+    field_path: String,
+    old: Option<Box<dyn PartialReflect>>,
+    new: Box<dyn PartialReflect>,
+    label: String,
+}
+
+impl SetReflectFieldCommand {
+    pub fn new<C: Component + Reflect + TypePath>(
+        entity: Entity,
+        field_path: impl Into<String>,
+        value: impl PartialReflect,
+    ) -> Self {
+        Self::from_type_path(entity, C::type_path(), field_path, Box::new(value))
+    }
+
+    /// Build the command from a component type path (for generic reflection-driven editors)
+    pub fn from_type_path(
+        entity: Entity,
+        component_type_path: impl Into<String>,
+        field_path: impl Into<String>,
+        value: Box<dyn PartialReflect>,
+    ) -> Self {
+        let component_type_path = component_type_path.into();
+        let field_path = field_path.into();
+        let short_name = component_type_path
+            .rsplit("::")
+            .next()
+            .unwrap_or(&component_type_path)
+            .to_string();
+
+        Self {
+            entity: entity.into(),
+            label: format!("Set {}.{}", short_name, field_path),
+            component_type_path,
+            field_path,
+            old: None,
+            new: value,
+        }
+    }
+
+    /// Apply `value` to the field of `entity`, returning the previous value
+    fn apply(&self, world: &mut World, entity: Entity, value: &dyn PartialReflect) -> Option<Box<dyn PartialReflect>> {
+        let type_registry = world.resource::<AppTypeRegistry>().clone();
+        let type_registry = type_registry.read();
+
+        let Some(reflect_component) = type_registry
+            .get_with_type_path(&self.component_type_path)
+            .and_then(|registration| registration.data::<ReflectComponent>())
+        else {
+            warn!("Component {} is not registered for reflection", self.component_type_path);
+            return None;
+        };
+
+        let mut entity = world.get_entity_mut(entity).ok()?;
+        let mut component = reflect_component.reflect_mut(&mut entity)?;
+
+        let field = match component.reflect_path_mut(self.field_path.as_str()) {
+            Ok(field) => field,
+            Err(e) => {
+                warn!("Invalid field path '{}': {}", self.field_path, e);
+                return None;
+            }
+        };
+
+        let previous = field.to_dynamic();
+        if let Err(e) = field.try_apply(value) {
+            warn!("Failed to set {}: {}", self.label, e);
+            return None;
+        }
+
+        Some(previous)
+    }
+}
+
+impl Command for SetReflectFieldCommand {
+    fn execute(&mut self, world: &mut World) {
+        let entity = self.entity.resolve(world);
+        let new = self.new.to_dynamic();
+        self.old = self.apply(world, entity, new.as_ref());
+    }
+
+    fn undo(&mut self, world: &mut World) {
+        let entity = self.entity.resolve(world);
+        if let Some(old) = self.old.take() {
+            self.apply(world, entity, old.as_ref());
+        }
+    }
+
+    fn name(&self) -> &str {
+        &self.label
+    }
+
+    fn memory_usage(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.label.len()
+            + self.component_type_path.len()
+            + self.field_path.len()
+            + reflect_memory_usage(&*self.new)
+            + self.old.as_ref().map_or(0, |old| reflect_memory_usage(&**old))
+    }
+}
+
+/// Group of commands that is executed and undone as a single history entry
+pub struct CompositeCommand {
+    label: String,
+    commands: Vec<Box<dyn Command>>,
+}
+
+impl CompositeCommand {
+    pub fn new(label: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            commands: Vec::new(),
+        }
+    }
+
+    /// Add a command to the group (builder style)
+    pub fn with(mut self, command: impl Command + 'static) -> Self {
+        self.commands.push(Box::new(command));
+        self
+    }
+
+    /// Add a command to the group
+    pub fn push(&mut self, command: Box<dyn Command>) {
+        self.commands.push(command);
+    }
+
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Commands in this group, in execution order
+    pub fn commands(&self) -> &[Box<dyn Command>] {
+        &self.commands
+    }
+
+    /// Take the grouped commands out of the composite
+    pub fn into_commands(self) -> Vec<Box<dyn Command>> {
+        self.commands
+    }
+
+    /// Record a command that has already been executed, merging it into the last one if possible
+    pub(crate) fn record(&mut self, command: Box<dyn Command>) {
+        if let Some(last) = self.commands.last_mut()
+            && last.can_merge(&*command)
+        {
+            last.merge(command);
+            return;
+        }
+
+        self.commands.push(command);
+    }
+}
+
+impl Command for CompositeCommand {
+    fn execute(&mut self, world: &mut World) {
+        for command in &mut self.commands {
+            command.execute(world);
+        }
+    }
+
+    fn undo(&mut self, world: &mut World) {
+        // Undo in reverse so later commands see the state they were executed in
+        for command in self.commands.iter_mut().rev() {
+            command.undo(world);
+        }
+    }
+
+    fn redo(&mut self, world: &mut World) {
+        for command in &mut self.commands {
+            command.redo(world);
+        }
+    }
+
+    fn name(&self) -> &str {
+        &self.label
+    }
+
+    fn memory_usage(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.label.len()
+            + self.commands.iter().map(|command| command.memory_usage()).sum::<usize>()
+    }
+
+    fn modifies_scene(&self) -> bool {
+        self.commands.iter().any(|command| command.modifies_scene())
+    }
+
+    fn can_merge(&self, other: &dyn Command) -> bool {
+        let Some(other) = (other as &dyn Any).downcast_ref::<Self>() else {
+            return false;
+        };
+
+        // Groups merge when every command merges with its counterpart (e.g. a multi-entity drag)
+        self.label == other.label
+            && self.commands.len() == other.commands.len()
+            && self
+                .commands
+                .iter()
+                .zip(&other.commands)
+                .all(|(command, other)| command.can_merge(&**other))
+    }
+
+    fn merge(&mut self, other: Box<dyn Command>) {
+        let other: Box<dyn Any> = other;
+        if let Ok(other) = other.downcast::<Self>() {
+            for (command, other) in self.commands.iter_mut().zip(other.commands) {
+                command.merge(other);
+            }
+        }
+    }
+}
+
+/// Selection whose entities are tracked by `EditorId`, so it can be restored after they're recreated
+#[derive(Clone)]
+struct StableSelection {
+    selected: Vec<StableEntity>,
+    primary: Option<StableEntity>,
+}
+
+impl StableSelection {
+    /// Capture `selection`, picking up the ids of its entities right away
+    fn capture(world: &World, selection: &EditorSelection) -> Self {
+        Self {
+            selected: selection.selected().map(|entity| StableEntity::new(world, entity)).collect(),
+            primary: selection.primary().map(|entity| StableEntity::new(world, entity)),
+        }
+    }
+
+    /// The selection pointing at the entities that currently carry the captured ids
+    fn resolve(&mut self, world: &World) -> EditorSelection {
+        let mut selection = EditorSelection::new();
+        for entity in &mut self.selected {
+            selection.add(entity.resolve(world));
+        }
+        if let Some(primary) = &mut self.primary {
+            selection.set_primary(primary.resolve(world));
+        }
+        selection
+    }
+
+    fn memory_usage(&self) -> usize {
+        (self.selected.len() + 1) * std::mem::size_of::<StableEntity>()
+    }
+}
+
+impl From<&EditorSelection> for StableSelection {
+    /// Ids are picked up when the selection is first resolved
+    fn from(selection: &EditorSelection) -> Self {
+        Self {
+            selected: selection.selected().map(StableEntity::from).collect(),
+            primary: selection.primary().map(StableEntity::from),
+        }
+    }
+}
+
+/// Change the editor selection
+///
+/// Consecutive selection changes within `SELECTION_MERGE_WINDOW` merge into one entry.
+pub struct SetSelectionCommand {
+    old: StableSelection,
+    new: StableSelection,
+    timestamp: Instant,
+}
+
+impl SetSelectionCommand {
+    pub fn new(old: EditorSelection, new: EditorSelection) -> Self {
+        Self {
+            old: StableSelection::from(&old),
+            new: StableSelection::from(&new),
+            timestamp: Instant::now(),
+        }
+    }
+}
+
+impl Command for SetSelectionCommand {
+    fn execute(&mut self, world: &mut World) {
+        // Pick up the ids of the previous selection while its entities still exist
+        self.old.resolve(world);
+        let selection = self.new.resolve(world);
+        world.insert_resource(selection);
+    }
+
+    fn undo(&mut self, world: &mut World) {
+        let selection = self.old.resolve(world);
+        world.insert_resource(selection);
+        world.write_message(SelectionChangedByHistory);
+    }
+
+    fn redo(&mut self, world: &mut World) {
+        self.execute(world);
+        world.write_message(SelectionChangedByHistory);
+    }
+
+    fn name(&self) -> &str {
+        "Change Selection"
+    }
+
+    fn modifies_scene(&self) -> bool {
+        false
+    }
+
+    fn can_merge(&self, other: &dyn Command) -> bool {
+        let Some(other) = (other as &dyn Any).downcast_ref::<Self>() else {
+            return false;
+        };
+
+        other.timestamp.saturating_duration_since(self.timestamp) < SELECTION_MERGE_WINDOW
+    }
+
+    fn merge(&mut self, other: Box<dyn Command>) {
+        let other: Box<dyn Any> = other;
+        if let Ok(other) = other.downcast::<Self>() {
+            self.new = other.new;
+            self.timestamp = other.timestamp;
+        }
+    }
+
+    fn memory_usage(&self) -> usize {
+        std::mem::size_of::<Self>() + self.old.memory_usage() + self.new.memory_usage()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::message::Messages;
+    use bevy_editor_core::{EditorId, EditorIdIndex};
+
+    fn test_world() -> World {
+        let mut world = World::new();
+        world.init_resource::<AppTypeRegistry>();
+        {
+            let registry = world.resource::<AppTypeRegistry>();
+            let mut registry = registry.write();
+            registry.register::<Transform>();
+            registry.register::<Name>();
+            registry.register::<EditorId>();
+        }
+        world.init_resource::<EditorIdIndex>();
+        world.init_resource::<EditorSelection>();
+        world.init_resource::<Messages<SelectionChangedByHistory>>();
+        world.init_resource::<Messages<EntityRemapped>>();
+        world
+    }
+
+    #[test]
+    fn commands_follow_recreated_entities() {
+        let mut world = test_world();
+        let id = EditorId::new();
+        let entity = world.spawn((Transform::default(), id)).id();
+
+        let mut command = SetTransformCommand::new(entity, Transform::default(), Transform::from_xyz(1.0, 0.0, 0.0));
+        command.execute(&mut world);
+
+        // Recreate the entity (as play mode or a scene reload would)
+        world.despawn(entity);
+        let recreated = world.spawn((Transform::from_xyz(1.0, 0.0, 0.0), id)).id();
+
+        command.undo(&mut world);
+        assert_eq!(command.entity(), recreated);
+        assert_eq!(world.get::<Transform>(recreated).unwrap().translation.x, 0.0);
+    }
+
+    #[test]
+    fn transform_steps_merge_by_merge_id() {
+        let mut world = test_world();
+        let entity = world.spawn((Transform::default(), EditorId::new())).id();
+        let step = |from: f32, to: f32, merge_id: u64| {
+            SetTransformCommand::new(entity, Transform::from_xyz(from, 0.0, 0.0), Transform::from_xyz(to, 0.0, 0.0))
+                .with_merge_id(merge_id)
+        };
+
+        let mut drag = step(0.0, 1.0, 7);
+        assert!(drag.can_merge(&step(1.0, 2.0, 7)));
+        assert!(!drag.can_merge(&step(1.0, 2.0, 8)));
+        assert!(!drag.can_merge(&SetTransformCommand::new(entity, Transform::default(), Transform::default())));
+
+        drag.merge(Box::new(step(1.0, 2.0, 7)));
+        drag.execute(&mut world);
+        assert_eq!(world.get::<Transform>(entity).unwrap().translation.x, 2.0);
+        // Undo goes back to where the first step started
+        drag.undo(&mut world);
+        assert_eq!(world.get::<Transform>(entity).unwrap().translation.x, 0.0);
+    }
+
+    #[test]
+    fn composite_undoes_in_reverse() {
+        let mut world = test_world();
+        let entity = world.spawn((Transform::default(), EditorId::new())).id();
+        let mut composite = CompositeCommand::new("Move Twice")
+            .with(SetTransformCommand::new(entity, Transform::default(), Transform::from_xyz(1.0, 0.0, 0.0)))
+            .with(SetTransformCommand::new(
+                entity,
+                Transform::from_xyz(1.0, 0.0, 0.0),
+                Transform::from_xyz(5.0, 0.0, 0.0),
+            ));
+
+        composite.execute(&mut world);
+        assert_eq!(world.get::<Transform>(entity).unwrap().translation.x, 5.0);
+        composite.undo(&mut world);
+        assert_eq!(world.get::<Transform>(entity).unwrap().translation.x, 0.0);
+        assert_eq!(composite.name(), "Move Twice");
+    }
+
+    #[test]
+    fn undo_despawn_restores_whole_selection() {
+        let mut world = test_world();
+        let a = world.spawn((Transform::default(), Name::new("A"), EditorId::new())).id();
+        let b = world.spawn((Transform::default(), EditorId::new())).id();
+        {
+            let mut selection = world.resource_mut::<EditorSelection>();
+            selection.add(b);
+            selection.set_primary(a);
+        }
+
+        let mut command = DespawnEntityCommand::new(a);
+        command.execute(&mut world);
+        assert!(world.get_entity(a).is_err());
+        assert_eq!(world.resource::<EditorSelection>().len(), 1);
+
+        command.undo(&mut world);
+        let restored = command.entity();
+        let selection = world.resource::<EditorSelection>();
+        assert_eq!(selection.primary(), Some(restored));
+        assert!(selection.is_selected(b));
+        assert_eq!(selection.len(), 2);
+        assert_eq!(world.get::<Name>(restored).map(Name::as_str), Some("A"));
+        // Entity-keyed editor state hears about the respawn
+        let remaps = world.resource::<Messages<EntityRemapped>>();
+        assert_eq!(remaps.len(), 1);
+    }
+
+    #[test]
+    fn reflect_memory_usage_counts_heap_data() {
+        let small: Vec<u32> = vec![0; 4];
+        let large: Vec<u32> = vec![0; 1000];
+
+        assert!(reflect_memory_usage(&large) >= 1000 * std::mem::size_of::<u32>());
+        assert!(reflect_memory_usage(&large) > reflect_memory_usage(&small));
+        assert!(reflect_memory_usage(&"x".repeat(500)) >= 500);
+    }
+
+    #[test]
+    fn reflect_memory_usage_walks_struct_fields() {
+        // translation + rotation + scale
+        assert!(reflect_memory_usage(&Transform::default()) >= 12 + 16 + 12);
+    }
+}
@@ -1,6 +1,12 @@
 //! Undo/redo system using command pattern
 
+use bevy::ecs::system::SystemState;
 use bevy::prelude::*;
+use bevy_editor_core::{
+    EditorPreferences, EditorSelection, EntityRemapped, PlayModeController, SelectionChangedByHistory, UiFocus,
+};
+use std::any::Any;
+use std::collections::VecDeque;
 
 pub mod commands;
 
@@ -14,7 +20,16 @@ impl Plugin for EditorUndoPlugin {
         app.init_resource::<CommandHistory>()
             .add_message::<UndoEvent>()
             .add_message::<RedoEvent>()
-            .add_systems(Update, (handle_undo_events, handle_redo_events));
+            .add_systems(Update, apply_undo_preferences)
+            // After Update so selection changes from commands are already applied
+            .add_systems(PostUpdate, record_selection_changes)
+            // Play mode changes are applied in First
+            .add_systems(PreUpdate, track_play_session)
+            .add_systems(Update, (
+                handle_undo_shortcuts,
+                handle_undo_events,
+                handle_redo_events,
+            ).chain());
     }
 }
 
@@ -27,7 +42,9 @@ pub struct UndoEvent;
 pub struct RedoEvent;
 
 /// Trait for undoable commands
-pub trait Command: Send + Sync {
+///
+/// `Any` lets `can_merge`/`merge` downcast the other command to a concrete type.
+pub trait Command: Send + Sync + Any {
     /// Execute the command
     fn execute(&mut self, world: &mut World);
 
@@ -42,59 +59,271 @@ pub trait Command: Send + Sync {
     /// Get command name for UI display
     fn name(&self) -> &str;
 
-    /// Check if this command can be merged with another
+    /// Check if this command can be merged with a newer one
     fn can_merge(&self, _other: &dyn Command) -> bool {
         false
     }
 
-    /// Merge this command with another
+    /// Merge a newer command into this one (only called when `can_merge` returned true)
     fn merge(&mut self, _other: Box<dyn Command>) {}
+
+    /// Approximate heap + inline size in bytes, used to bound history memory
+    ///
+    /// Commands holding snapshots or large values should override this.
+    fn memory_usage(&self) -> usize {
+        std::mem::size_of_val(self)
+    }
+
+    /// Whether the command changes the scene (and so marks it as modified)
+    ///
+    /// Editor-only commands like selection changes return `false`.
+    fn modifies_scene(&self) -> bool {
+        true
+    }
+}
+
+/// A command on one of the history stacks with its cached size
+struct HistoryEntry {
+    command: Box<dyn Command>,
+    memory: usize,
+    /// Unique id of the state reached after this command (changes when merged into)
+    id: u64,
+    /// Cached `Command::modifies_scene`
+    modifies_scene: bool,
+}
+
+impl HistoryEntry {
+    fn new(command: Box<dyn Command>, id: u64) -> Self {
+        let memory = command.memory_usage();
+        let modifies_scene = command.modifies_scene();
+        Self { command, memory, id, modifies_scene }
+    }
 }
 
 /// Manages command history for undo/redo
+///
+/// Both stacks are ring buffers: once the entry count or the approximate memory
+/// budget is exceeded, the oldest undo entries and furthest redo entries are dropped
+/// from the front.
 #[derive(Resource)]
 pub struct CommandHistory {
-    undo_stack: Vec<Box<dyn Command>>,
-    redo_stack: Vec<Box<dyn Command>>,
+    undo_stack: VecDeque<HistoryEntry>,
+    redo_stack: VecDeque<HistoryEntry>,
     max_history: usize,
+    /// Approximate memory budget in bytes (`None` = unlimited)
+    max_memory: Option<usize>,
+    /// Approximate memory used by both stacks
+    memory_usage: usize,
+    /// Open transaction collecting executed commands into one entry
+    transaction: Option<CompositeCommand>,
+    /// Next state id to hand out
+    next_id: u64,
+    /// State id below the bottom of the undo stack (initial state or last evicted entry)
+    base_id: u64,
+    /// State id at the last save (`None` = that state is no longer reachable)
+    save_point: Option<u64>,
+    /// Open play session, whose entries are discarded when it ends
+    play_session: Option<PlaySession>,
+}
+
+/// History state from before Play, restored on Stop
+struct PlaySession {
+    /// Id of the newest entry before Play (older entries can't be undone during play)
+    top_id: Option<u64>,
+    /// Scene state id before Play
+    state_id: u64,
+    /// Redo stack from before Play, set aside so play edits don't clear it
+    redo_stack: VecDeque<HistoryEntry>,
 }
 
 impl Default for CommandHistory {
     fn default() -> Self {
         Self {
-            undo_stack: Vec::new(),
-            redo_stack: Vec::new(),
+            undo_stack: VecDeque::new(),
+            redo_stack: VecDeque::new(),
             max_history: 100,
+            max_memory: None,
+            memory_usage: 0,
+            transaction: None,
+            next_id: 1,
+            base_id: 0,
+            save_point: Some(0),
+            play_session: None,
         }
     }
 }
 
 impl CommandHistory {
+    pub fn with_limits(max_history: usize, max_memory: Option<usize>) -> Self {
+        Self {
+            max_history,
+            max_memory,
+            ..default()
+        }
+    }
+
+    /// Change the history limits, evicting old entries if needed
+    pub fn set_limits(&mut self, max_history: usize, max_memory: Option<usize>) {
+        self.max_history = max_history;
+        self.max_memory = max_memory;
+        self.enforce_limits();
+    }
+
     pub fn execute(&mut self, mut command: Box<dyn Command>, world: &mut World) {
         command.execute(world);
 
-        // Try to merge with previous command
-        if let Some(last) = self.undo_stack.last_mut() {
-            if last.can_merge(&*command) {
-                last.merge(command);
-                self.redo_stack.clear();
-                return;
+        // Inside a transaction the command becomes part of the pending group
+        if let Some(transaction) = &mut self.transaction {
+            transaction.record(command);
+            return;
+        }
+
+        self.push(command);
+    }
+
+    /// Push an already executed command onto the undo stack
+    fn push(&mut self, command: Box<dyn Command>) {
+        // Branching off: the undone states can't be reached any more
+        for entry in std::mem::take(&mut self.redo_stack) {
+            self.discard(entry);
+        }
+
+        // Try to merge with previous command (never with one from before Play)
+        let session_top = self.play_session.as_ref().and_then(|session| session.top_id);
+        if let Some(last) = self.undo_stack.back_mut()
+            && session_top.is_none_or(|top| last.id > top)
+            && last.command.can_merge(&*command)
+        {
+            last.command.merge(command);
+            // The state after the unmerged entry is gone
+            if last.modifies_scene && self.save_point == Some(last.id) {
+                self.save_point = None;
             }
+            let memory = last.command.memory_usage();
+            self.memory_usage = self.memory_usage - last.memory + memory;
+            last.memory = memory;
+            last.modifies_scene = last.command.modifies_scene();
+            // The merged entry leads to a different state than before
+            last.id = self.next_id;
+            self.next_id += 1;
+            self.enforce_limits();
+            return;
         }
 
-        self.undo_stack.push(command);
-        self.redo_stack.clear();
+        let entry = HistoryEntry::new(command, self.next_id);
+        self.next_id += 1;
+        self.memory_usage += entry.memory;
+        self.undo_stack.push_back(entry);
+        self.enforce_limits();
+    }
+
+    fn over_memory_budget(&self) -> bool {
+        self.max_memory.is_some_and(|max| self.memory_usage > max)
+    }
+
+    /// Drop entries until both limits are met (the newest undo entry is always kept)
+    ///
+    /// Over the memory budget the furthest redo entries go first, so a large redo
+    /// tail can't push out undo history.
+    fn enforce_limits(&mut self) {
+        while self.over_memory_budget()
+            && let Some(entry) = self.redo_stack.pop_front()
+        {
+            self.discard(entry);
+        }
+
+        while self.undo_stack.len() > 1
+            && (self.undo_stack.len() > self.max_history || self.over_memory_budget())
+        {
+            if let Some(entry) = self.undo_stack.pop_front() {
+                self.memory_usage -= entry.memory;
+                // Entries that leave the scene alone don't change the base scene state
+                if entry.modifies_scene {
+                    // The state before the evicted entry can't be undone to any more
+                    if self.save_point == Some(self.base_id) {
+                        self.save_point = None;
+                    }
+                    self.base_id = entry.id;
+                }
+            }
+        }
+    }
+
+    /// Drop an entry that is no longer reachable, forgetting the save point if it led there
+    fn discard(&mut self, entry: HistoryEntry) {
+        self.memory_usage -= entry.memory;
+        if entry.modifies_scene && self.save_point == Some(entry.id) {
+            self.save_point = None;
+        }
+    }
+
+    /// Move an entry between stacks, running `apply` on it and refreshing its size
+    fn apply_entry(&mut self, entry: &mut HistoryEntry, apply: impl FnOnce(&mut dyn Command)) {
+        apply(&mut *entry.command);
+        let memory = entry.command.memory_usage();
+        self.memory_usage = self.memory_usage - entry.memory + memory;
+        entry.memory = memory;
+    }
 
-        // Limit history size
-        if self.undo_stack.len() > self.max_history {
-            self.undo_stack.remove(0);
+    /// Start grouping executed commands into a single undo entry named `name`
+    pub fn begin_transaction(&mut self, name: impl Into<String>) {
+        if self.transaction.is_some() {
+            warn!("Transaction already in progress, ignoring nested begin");
+            return;
         }
+
+        self.transaction = Some(CompositeCommand::new(name));
+    }
+
+    /// Finish the open transaction and push it as one undo entry
+    pub fn commit_transaction(&mut self) {
+        let Some(transaction) = self.transaction.take() else {
+            warn!("No transaction to commit");
+            return;
+        };
+
+        match transaction.len() {
+            0 => {}
+            // A single command doesn't need the wrapper
+            1 => {
+                if let Some(command) = transaction.into_commands().pop() {
+                    self.push(command);
+                }
+            }
+            _ => self.push(Box::new(transaction)),
+        }
+    }
+
+    /// Abort the open transaction, rolling back the commands it already ran
+    pub fn cancel_transaction(&mut self, world: &mut World) {
+        let Some(mut transaction) = self.transaction.take() else {
+            warn!("No transaction to cancel");
+            return;
+        };
+
+        transaction.undo(world);
+    }
+
+    pub fn in_transaction(&self) -> bool {
+        self.transaction.is_some()
     }
 
     pub fn undo(&mut self, world: &mut World) -> bool {
-        if let Some(mut command) = self.undo_stack.pop() {
-            command.undo(world);
-            self.redo_stack.push(command);
+        if self.in_transaction() {
+            warn!("Cannot undo while a transaction is in progress");
+            return false;
+        }
+
+        // Edits from before Play stay put until it's stopped
+        if let Some(top) = self.play_session.as_ref().and_then(|session| session.top_id)
+            && self.undo_stack.back().is_some_and(|entry| entry.id <= top)
+        {
+            return false;
+        }
+
+        if let Some(mut entry) = self.undo_stack.pop_back() {
+            self.apply_entry(&mut entry, |command| command.undo(world));
+            self.redo_stack.push_back(entry);
             true
         } else {
             false
@@ -102,15 +331,75 @@ impl CommandHistory {
     }
 
     pub fn redo(&mut self, world: &mut World) -> bool {
-        if let Some(mut command) = self.redo_stack.pop() {
-            command.redo(world);
-            self.undo_stack.push(command);
+        if self.in_transaction() {
+            warn!("Cannot redo while a transaction is in progress");
+            return false;
+        }
+
+        if let Some(mut entry) = self.redo_stack.pop_back() {
+            self.apply_entry(&mut entry, |command| command.redo(world));
+            self.undo_stack.push_back(entry);
+            self.enforce_limits();
             true
         } else {
             false
         }
     }
 
+    /// Move through the history until `position` commands are applied
+    ///
+    /// Positions match `position()`: 0 is the initial state, `len()` is the newest entry.
+    pub fn jump_to(&mut self, position: usize, world: &mut World) {
+        while self.position() > position {
+            if !self.undo(world) {
+                break;
+            }
+        }
+
+        while self.position() < position {
+            if !self.redo(world) {
+                break;
+            }
+        }
+    }
+
+    /// Undoable commands, oldest first
+    pub fn undo_commands(&self) -> impl DoubleEndedIterator<Item = &dyn Command> {
+        self.undo_stack.iter().map(|entry| &*entry.command)
+    }
+
+    /// Redoable commands, in the order they would be redone
+    pub fn redo_commands(&self) -> impl DoubleEndedIterator<Item = &dyn Command> {
+        self.redo_stack.iter().rev().map(|entry| &*entry.command)
+    }
+
+    /// Number of applied commands (the current position in the history)
+    pub fn position(&self) -> usize {
+        self.undo_stack.len()
+    }
+
+    /// Total number of entries (applied and undone)
+    pub fn len(&self) -> usize {
+        self.undo_stack.len() + self.redo_stack.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn max_history(&self) -> usize {
+        self.max_history
+    }
+
+    pub fn max_memory(&self) -> Option<usize> {
+        self.max_memory
+    }
+
+    /// Approximate memory used by the undo and redo stacks, in bytes
+    pub fn memory_usage(&self) -> usize {
+        self.memory_usage
+    }
+
     pub fn can_undo(&self) -> bool {
         !self.undo_stack.is_empty()
     }
@@ -119,30 +408,547 @@ impl CommandHistory {
         !self.redo_stack.is_empty()
     }
 
+    /// Id of the current scene state (changes whenever a scene-modifying command is
+    /// executed, undone or redone; entries that don't modify the scene are skipped)
+    pub fn state_id(&self) -> u64 {
+        self.undo_stack
+            .iter()
+            .rev()
+            .find(|entry| entry.modifies_scene)
+            .map_or(self.base_id, |entry| entry.id)
+    }
+
+    /// Remember the current state as saved
+    pub fn mark_saved(&mut self) {
+        self.save_point = Some(self.state_id());
+    }
+
+    /// Whether the current state differs from the last saved one
+    pub fn is_modified(&self) -> bool {
+        self.save_point != Some(self.state_id())
+    }
+
+    /// Drop all entries; the resulting state counts as modified until `mark_saved`
     pub fn clear(&mut self) {
+        self.save_point = None;
         self.undo_stack.clear();
         self.redo_stack.clear();
+        self.memory_usage = 0;
+        self.transaction = None;
+        self.play_session = None;
+        self.base_id = self.next_id;
+        self.next_id += 1;
+    }
+
+    /// Start recording a play session: edits made until `end_play_session` are
+    /// undoable among themselves, then dropped without being undone
+    pub fn begin_play_session(&mut self) {
+        if self.play_session.is_some() {
+            return;
+        }
+
+        let redo_stack = std::mem::take(&mut self.redo_stack);
+        self.memory_usage -= redo_stack.iter().map(|entry| entry.memory).sum::<usize>();
+
+        self.play_session = Some(PlaySession {
+            top_id: self.undo_stack.back().map(|entry| entry.id),
+            state_id: self.state_id(),
+            redo_stack,
+        });
+    }
+
+    /// Drop the entries recorded since `begin_play_session` (the scene itself is
+    /// restored by play mode) and bring back the history from before
+    pub fn end_play_session(&mut self) {
+        let Some(session) = self.play_session.take() else {
+            return;
+        };
+
+        self.transaction = None;
+        for entry in std::mem::take(&mut self.redo_stack) {
+            self.discard(entry);
+        }
+        while self
+            .undo_stack
+            .back()
+            .is_some_and(|entry| session.top_id.is_none_or(|top| entry.id > top))
+        {
+            if let Some(entry) = self.undo_stack.pop_back() {
+                self.discard(entry);
+            }
+        }
+
+        // Everything older may have been evicted during play
+        if self.undo_stack.is_empty() {
+            self.base_id = session.state_id;
+        }
+
+        self.memory_usage += session.redo_stack.iter().map(|entry| entry.memory).sum::<usize>();
+        self.redo_stack = session.redo_stack;
+    }
+
+    pub fn in_play_session(&self) -> bool {
+        self.play_session.is_some()
+    }
+}
+
+/// Extension trait for recording undoable commands from regular systems
+///
+/// Systems only get deferred world access, so the command is queued and
+/// executed against `CommandHistory` when commands are applied.
+pub trait UndoCommandsExt {
+    /// Execute a command and push it onto the undo stack
+    fn execute_undoable(&mut self, command: impl Command + 'static);
+
+    /// Start a transaction; undoable commands queued after this are grouped
+    fn begin_undo_transaction(&mut self, name: impl Into<String>);
+
+    /// Commit the open transaction as a single undo entry
+    fn commit_undo_transaction(&mut self);
+
+    /// Cancel the open transaction, rolling back what it already executed
+    fn cancel_undo_transaction(&mut self);
+}
+
+impl UndoCommandsExt for Commands<'_, '_> {
+    fn execute_undoable(&mut self, command: impl Command + 'static) {
+        self.queue(move |world: &mut World| {
+            world.resource_scope(|world, mut history: Mut<CommandHistory>| {
+                history.execute(Box::new(command), world);
+            });
+        });
+    }
+
+    fn begin_undo_transaction(&mut self, name: impl Into<String>) {
+        let name = name.into();
+        self.queue(move |world: &mut World| {
+            world.resource_mut::<CommandHistory>().begin_transaction(name);
+        });
+    }
+
+    fn commit_undo_transaction(&mut self) {
+        self.queue(|world: &mut World| {
+            world.resource_mut::<CommandHistory>().commit_transaction();
+        });
+    }
+
+    fn cancel_undo_transaction(&mut self) {
+        self.queue(|world: &mut World| {
+            world.resource_scope(|world, mut history: Mut<CommandHistory>| {
+                history.cancel_transaction(world);
+            });
+        });
     }
 }
 
+/// Keep the history limits in sync with the editor preferences
+fn apply_undo_preferences(
+    preferences: Res<EditorPreferences>,
+    mut history: ResMut<CommandHistory>,
+) {
+    if !preferences.is_changed() {
+        return;
+    }
+
+    history.set_limits(preferences.undo.max_entries, preferences.undo.max_memory_bytes());
+}
+
+/// Keep edits made while playing out of the history once play mode is stopped
+fn track_play_session(
+    controller: Res<PlayModeController>,
+    mut history: ResMut<CommandHistory>,
+) {
+    let playing = !controller.is_stopped();
+    if playing == history.in_play_session() {
+        return;
+    }
+
+    if playing {
+        history.begin_play_session();
+    } else {
+        history.end_play_session();
+    }
+}
+
+/// Record user selection changes as undo entries (when enabled in preferences)
+///
+/// Selection changes announced by `SelectionChangedByHistory` (undo/redo, deletions)
+/// and remaps following recreated entities are only remembered, not recorded again.
+fn record_selection_changes(
+    selection: Res<EditorSelection>,
+    preferences: Res<EditorPreferences>,
+    mut by_history: MessageReader<SelectionChangedByHistory>,
+    mut remaps: MessageReader<EntityRemapped>,
+    mut last_selection: Local<EditorSelection>,
+    mut commands: Commands,
+) {
+    let from_history = by_history.read().count() > 0;
+    let remapped = remaps.read().count() > 0;
+
+    if !selection.is_changed() || *selection == *last_selection {
+        return;
+    }
+
+    let old = std::mem::replace(&mut *last_selection, selection.clone());
+
+    if preferences.undo.record_selection && !from_history && !remapped {
+        commands.execute_undoable(SetSelectionCommand::new(old, selection.clone()));
+    }
+}
+
+/// Map Ctrl+Z / Ctrl+Shift+Z / Ctrl+Y to undo and redo messages
+fn handle_undo_shortcuts(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    ui_focus: Res<UiFocus>,
+    mut undo_events: MessageWriter<UndoEvent>,
+    mut redo_events: MessageWriter<RedoEvent>,
+) {
+    // Text fields handle their own editing keys
+    if ui_focus.has_focus() {
+        return;
+    }
+
+    let ctrl = keyboard.pressed(KeyCode::ControlLeft) || keyboard.pressed(KeyCode::ControlRight);
+    let shift = keyboard.pressed(KeyCode::ShiftLeft) || keyboard.pressed(KeyCode::ShiftRight);
+
+    if !ctrl {
+        return;
+    }
+
+    if keyboard.just_pressed(KeyCode::KeyZ) {
+        if shift {
+            redo_events.write(RedoEvent);
+        } else {
+            undo_events.write(UndoEvent);
+        }
+    } else if keyboard.just_pressed(KeyCode::KeyY) {
+        redo_events.write(RedoEvent);
+    }
+}
+
+/// Apply pending undo messages (exclusive system - commands need `&mut World`)
 fn handle_undo_events(
-    mut events: MessageReader<UndoEvent>,
-    history: Res<CommandHistory>,
+    world: &mut World,
+    events: &mut SystemState<MessageReader<UndoEvent>>,
 ) {
-    for _ in events.read() {
-        // TODO: Implement undo with commands
-        // We'll need to refactor this to use Bevy's command system
-        // or deferred world access
+    let count = events.get_mut(world).read().count();
+
+    for _ in 0..count {
+        let undone = world.resource_scope(|world, mut history: Mut<CommandHistory>| {
+            history.undo(world)
+        });
+
+        if !undone {
+            break;
+        }
     }
 }
 
+/// Apply pending redo messages (exclusive system - commands need `&mut World`)
 fn handle_redo_events(
-    mut events: MessageReader<RedoEvent>,
-    history: Res<CommandHistory>,
+    world: &mut World,
+    events: &mut SystemState<MessageReader<RedoEvent>>,
 ) {
-    for _ in events.read() {
-        // TODO: Implement redo with commands
-        // We'll need to refactor this to use Bevy's command system
-        // or deferred world access
+    let count = events.get_mut(world).read().count();
+
+    for _ in 0..count {
+        let redone = world.resource_scope(|world, mut history: Mut<CommandHistory>| {
+            history.redo(world)
+        });
+
+        if !redone {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Resource, Default)]
+    struct Counter(i32);
+
+    /// Adds `amount` to `Counter`, reporting a fixed size
+    struct AddCommand {
+        amount: i32,
+        memory: usize,
+        /// Commands with the same key merge, like the steps of a drag
+        merge_key: Option<u32>,
+    }
+
+    impl AddCommand {
+        fn new(amount: i32) -> Self {
+            Self { amount, memory: 16, merge_key: None }
+        }
+
+        fn sized(amount: i32, memory: usize) -> Self {
+            Self { amount, memory, merge_key: None }
+        }
+
+        fn merging(amount: i32, merge_key: u32) -> Self {
+            Self { amount, memory: 16, merge_key: Some(merge_key) }
+        }
+    }
+
+    impl Command for AddCommand {
+        fn execute(&mut self, world: &mut World) {
+            world.resource_mut::<Counter>().0 += self.amount;
+        }
+
+        fn undo(&mut self, world: &mut World) {
+            world.resource_mut::<Counter>().0 -= self.amount;
+        }
+
+        fn name(&self) -> &str {
+            "Add"
+        }
+
+        fn memory_usage(&self) -> usize {
+            self.memory
+        }
+
+        fn can_merge(&self, other: &dyn Command) -> bool {
+            (other as &dyn Any)
+                .downcast_ref::<Self>()
+                .is_some_and(|other| self.merge_key.is_some() && self.merge_key == other.merge_key)
+        }
+
+        fn merge(&mut self, other: Box<dyn Command>) {
+            let other: Box<dyn Any> = other;
+            if let Ok(other) = other.downcast::<Self>() {
+                self.amount += other.amount;
+            }
+        }
+    }
+
+    fn setup() -> World {
+        let mut world = World::new();
+        world.init_resource::<Counter>();
+        world
+    }
+
+    fn counter(world: &World) -> i32 {
+        world.resource::<Counter>().0
+    }
+
+    #[test]
+    fn entry_limit_drops_oldest() {
+        let mut world = setup();
+        let mut history = CommandHistory::with_limits(3, None);
+
+        for amount in 1..=5 {
+            history.execute(Box::new(AddCommand::new(amount)), &mut world);
+        }
+
+        assert_eq!(history.position(), 3);
+        while history.undo(&mut world) {}
+        // Only 3 + 4 + 5 could be undone
+        assert_eq!(counter(&world), 1 + 2);
+    }
+
+    #[test]
+    fn memory_limit_keeps_newest_entry() {
+        let mut world = setup();
+        let mut history = CommandHistory::with_limits(100, Some(250));
+
+        history.execute(Box::new(AddCommand::sized(1, 100)), &mut world);
+        history.execute(Box::new(AddCommand::sized(2, 100)), &mut world);
+        assert_eq!(history.position(), 2);
+
+        history.execute(Box::new(AddCommand::sized(3, 100)), &mut world);
+        assert_eq!(history.position(), 2);
+        assert_eq!(history.memory_usage(), 200);
+
+        // A single entry over budget is still kept
+        history.execute(Box::new(AddCommand::sized(4, 1000)), &mut world);
+        assert_eq!(history.position(), 1);
+        assert_eq!(history.memory_usage(), 1000);
+    }
+
+    #[test]
+    fn memory_limit_trims_redo_before_undo() {
+        let mut world = setup();
+        let mut history = CommandHistory::with_limits(100, None);
+
+        for amount in 1..=4 {
+            history.execute(Box::new(AddCommand::sized(amount, 100)), &mut world);
+        }
+        history.undo(&mut world);
+        history.undo(&mut world);
+        assert_eq!(history.len(), 4);
+
+        history.set_limits(100, Some(300));
+
+        // The furthest redo entry goes, the undo history stays intact
+        assert_eq!(history.position(), 2);
+        assert_eq!(history.len(), 3);
+        assert!(history.redo(&mut world));
+        assert!(!history.redo(&mut world));
+        assert_eq!(counter(&world), 1 + 2 + 3);
+    }
+
+    #[test]
+    fn undo_back_to_save_point_is_unmodified() {
+        let mut world = setup();
+        let mut history = CommandHistory::default();
+        assert!(!history.is_modified());
+
+        history.execute(Box::new(AddCommand::new(1)), &mut world);
+        assert!(history.is_modified());
+        history.mark_saved();
+
+        history.execute(Box::new(AddCommand::new(2)), &mut world);
+        assert!(history.is_modified());
+        history.undo(&mut world);
+        assert!(!history.is_modified());
+    }
+
+    #[test]
+    fn branching_off_forgets_save_point() {
+        let mut world = setup();
+        let mut history = CommandHistory::default();
+
+        history.execute(Box::new(AddCommand::new(1)), &mut world);
+        history.mark_saved();
+        history.undo(&mut world);
+        history.execute(Box::new(AddCommand::new(2)), &mut world);
+
+        assert_eq!(history.save_point, None);
+        history.undo(&mut world);
+        assert!(history.is_modified());
+    }
+
+    #[test]
+    fn evicting_saved_state_forgets_save_point() {
+        let mut world = setup();
+        let mut history = CommandHistory::with_limits(2, None);
+        history.mark_saved();
+
+        history.execute(Box::new(AddCommand::new(1)), &mut world);
+        history.execute(Box::new(AddCommand::new(2)), &mut world);
+        assert_eq!(history.save_point, Some(0));
+
+        // The initial (saved) state can no longer be undone to
+        history.execute(Box::new(AddCommand::new(3)), &mut world);
+        assert_eq!(history.save_point, None);
+        while history.undo(&mut world) {}
+        assert!(history.is_modified());
+    }
+
+    #[test]
+    fn merged_commands_undo_together() {
+        let mut world = setup();
+        let mut history = CommandHistory::default();
+
+        for amount in 1..=3 {
+            history.execute(Box::new(AddCommand::merging(amount, 1)), &mut world);
+        }
+        // A different key starts a new entry
+        history.execute(Box::new(AddCommand::merging(10, 2)), &mut world);
+        assert_eq!(history.len(), 2);
+        assert_eq!(counter(&world), 16);
+
+        history.undo(&mut world);
+        history.undo(&mut world);
+        assert_eq!(counter(&world), 0);
+        history.redo(&mut world);
+        assert_eq!(counter(&world), 6);
+    }
+
+    #[test]
+    fn merging_changes_state_id() {
+        let mut world = setup();
+        let mut history = CommandHistory::default();
+
+        history.execute(Box::new(AddCommand::merging(1, 1)), &mut world);
+        history.mark_saved();
+        history.execute(Box::new(AddCommand::merging(1, 1)), &mut world);
+
+        assert_eq!(history.len(), 1);
+        assert!(history.is_modified());
+    }
+
+    #[test]
+    fn transaction_is_one_entry() {
+        let mut world = setup();
+        let mut history = CommandHistory::default();
+
+        history.begin_transaction("Add Three");
+        for amount in 1..=3 {
+            history.execute(Box::new(AddCommand::new(amount)), &mut world);
+        }
+        // Nothing to undo until the group is committed
+        assert!(!history.undo(&mut world));
+        history.commit_transaction();
+
+        assert_eq!(history.len(), 1);
+        assert_eq!(history.undo_commands().next_back().map(|command| command.name()), Some("Add Three"));
+        history.undo(&mut world);
+        assert_eq!(counter(&world), 0);
+        history.redo(&mut world);
+        assert_eq!(counter(&world), 6);
+    }
+
+    #[test]
+    fn transaction_merges_its_own_commands() {
+        let mut world = setup();
+        let mut history = CommandHistory::default();
+
+        history.begin_transaction("Drag");
+        history.execute(Box::new(AddCommand::merging(1, 1)), &mut world);
+        history.execute(Box::new(AddCommand::merging(2, 1)), &mut world);
+        history.commit_transaction();
+
+        // Merged down to one command, pushed without the group wrapper
+        assert_eq!(history.undo_commands().next_back().map(|command| command.name()), Some("Add"));
+        history.undo(&mut world);
+        assert_eq!(counter(&world), 0);
+    }
+
+    #[test]
+    fn cancelled_transaction_rolls_back() {
+        let mut world = setup();
+        let mut history = CommandHistory::default();
+        history.execute(Box::new(AddCommand::new(1)), &mut world);
+
+        history.begin_transaction("Add Two");
+        history.execute(Box::new(AddCommand::new(2)), &mut world);
+        history.execute(Box::new(AddCommand::new(3)), &mut world);
+        history.cancel_transaction(&mut world);
+
+        assert!(!history.in_transaction());
+        assert_eq!(history.len(), 1);
+        assert_eq!(counter(&world), 1);
+    }
+
+    #[test]
+    fn empty_transaction_adds_nothing() {
+        let mut history = CommandHistory::default();
+        history.begin_transaction("Nothing");
+        history.commit_transaction();
+
+        assert!(history.is_empty());
+        assert!(!history.is_modified());
+    }
+
+    #[test]
+    fn jump_to_moves_both_ways() {
+        let mut world = setup();
+        let mut history = CommandHistory::default();
+        for amount in [1, 10, 100] {
+            history.execute(Box::new(AddCommand::new(amount)), &mut world);
+        }
+
+        history.jump_to(1, &mut world);
+        assert_eq!(history.position(), 1);
+        assert_eq!(counter(&world), 1);
+
+        history.jump_to(3, &mut world);
+        assert_eq!(counter(&world), 111);
+
+        // Out of range stops at the newest entry
+        history.jump_to(10, &mut world);
+        assert_eq!(history.position(), 3);
     }
 }
@@ -1,8 +1,62 @@
 //! Editor camera controller with orbit, pan, and zoom
+//!
+//! The viewport has a 2D camera (orthographic, pan and zoom) and a 3D camera (perspective,
+//! orbiting `focus`). `ViewportMode` picks which one is active.
+//!
+//! F frames the selection, easing the camera there over `CameraPreferences::frame_duration`.
+//! Holding Shift+F keeps following the selection as it moves, until either key is released.
+//!
+//! Every `EditorViewport` gets its own pair of cameras (see `setup_viewports`); camera input
+//! only goes to the hovered viewport and framing to the last hovered one. A separate UI
+//! camera draws the editor UI.
 
 use bevy::prelude::*;
+use bevy::camera::primitives::Aabb;
+use bevy::camera::RenderTarget;
+use bevy::camera::visibility::RenderLayers;
 use bevy::input::mouse::{MouseMotion, MouseWheel};
-use bevy_editor_core::EditorEntity;
+use bevy::ui::IsDefaultUiCamera;
+use bevy_editor_core::{EditorEntity, EditorPreferences, EditorSelection, UiFocus};
+use crate::{
+    ActiveViewport, ViewportCameras, ViewportLayers, attach_viewport_camera, setup_viewports, sprite_bounds,
+    update_active_viewport,
+};
+
+/// Whether the viewport edits in 2D or 3D
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Resource)]
+pub enum ViewportMode {
+    #[default]
+    TwoD,
+    ThreeD,
+}
+
+impl ViewportMode {
+    /// The other mode, for toggling with a shortcut or toolbar button
+    pub fn toggled(self) -> Self {
+        match self {
+            Self::TwoD => Self::ThreeD,
+            Self::ThreeD => Self::TwoD,
+        }
+    }
+
+    /// Run condition for systems that only apply in 2D mode
+    pub fn is_2d(mode: Res<ViewportMode>) -> bool {
+        *mode == Self::TwoD
+    }
+
+    /// Run condition for systems that only apply in 3D mode
+    pub fn is_3d(mode: Res<ViewportMode>) -> bool {
+        *mode == Self::ThreeD
+    }
+}
+
+/// Marker for the orthographic editor camera used in 2D mode
+#[derive(Component)]
+pub struct EditorCamera2d;
+
+/// Marker for the perspective editor camera used in 3D mode
+#[derive(Component)]
+pub struct EditorCamera3d;
 
 /// Marker component for the editor camera
 #[derive(Component)]
@@ -18,6 +72,9 @@ pub struct EditorCamera {
     pub orbit_sensitivity: f32,
     pub pan_sensitivity: f32,
     pub zoom_sensitivity: f32,
+    /// Zoom limits for `radius`
+    pub min_radius: f32,
+    pub max_radius: f32,
     /// Enabled state
     pub enabled: bool,
 }
@@ -31,33 +88,99 @@ impl Default for EditorCamera {
             pitch: std::f32::consts::FRAC_PI_4, // 45 degrees
             orbit_sensitivity: 0.003,
             pan_sensitivity: 0.01,
-            zoom_sensitivity: 0.1,
+            zoom_sensitivity: 1.0,
+            min_radius: 0.5,
+            max_radius: 500.0,
             enabled: true,
         }
     }
 }
 
+/// Message asking the active editor camera to frame the current selection
+#[derive(Message, Debug, Clone, Copy, Default)]
+pub struct FrameSelection;
+
+/// Whether the active camera keeps the selection centered as it moves (while Shift+F is held)
+#[derive(Resource, Debug, Default)]
+pub struct CameraFollow {
+    pub active: bool,
+}
+
+/// In-progress animation of an editor camera's `focus` and `radius`
+#[derive(Component, Debug, Clone, Copy)]
+pub struct CameraTransition {
+    pub from_focus: Vec3,
+    pub from_radius: f32,
+    pub to_focus: Vec3,
+    pub to_radius: f32,
+    /// Seconds since the transition started (real time, so it runs while the game is paused)
+    pub elapsed: f32,
+    pub duration: f32,
+}
+
+impl CameraTransition {
+    /// Transition from the camera's current focus and radius
+    pub fn new(camera: &EditorCamera, to_focus: Vec3, to_radius: f32, duration: f32) -> Self {
+        Self {
+            from_focus: camera.focus,
+            from_radius: camera.radius,
+            to_focus,
+            to_radius,
+            elapsed: 0.0,
+            duration,
+        }
+    }
+}
+
+/// Extra room around framed bounds
+const FRAME_MARGIN: f32 = 1.5;
+/// Size given to selected entities without bounds (a light, an empty), in world units
+const POINT_BOUNDS_SIZE: f32 = 0.5;
+
 /// Plugin for editor camera functionality
 pub struct EditorCameraPlugin;
 
 impl Plugin for EditorCameraPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, spawn_editor_camera)
+        app.init_resource::<ViewportMode>()
+            .init_resource::<CameraFollow>()
+            .add_message::<FrameSelection>()
+            .init_resource::<ActiveViewport>()
+            .init_resource::<ViewportLayers>()
+            .add_systems(Startup, spawn_ui_camera)
             .add_systems(Update, (
+                setup_viewports,
+                update_active_viewport,
+                switch_viewport_mode,
+                attach_viewport_camera,
                 editor_camera_orbit,
                 editor_camera_pan,
                 editor_camera_zoom,
+                handle_frame_shortcuts,
+                frame_selection,
+                follow_selection,
+                animate_camera_transition,
                 update_camera_transform,
+                update_camera_transform_3d,
             ).chain());
     }
 }
 
-fn spawn_editor_camera(mut commands: Commands) {
-    // Spawn the 2D camera for the editor viewport
+/// Spawn a viewport's 2D camera, rendering to `target` and seeing `layers`
+pub(crate) fn spawn_editor_camera(
+    commands: &mut Commands,
+    target: RenderTarget,
+    layers: RenderLayers,
+    active: bool,
+) -> Entity {
     // All components must be added together in a single spawn() to allow Bevy's
     // required components system to process everything atomically
     commands.spawn((
         Camera2d,
+        Camera {
+            is_active: active,
+            ..default()
+        },
         // For 2D in Bevy, camera should be at Z=999.9 (or any positive Z > sprites)
         // This is the default for 2D cameras - sprites are at Z=0
         Transform::from_xyz(0.0, 0.0, 999.9),
@@ -75,39 +198,97 @@ fn spawn_editor_camera(mut commands: Commands) {
             orbit_sensitivity: 0.003,
             pan_sensitivity: 1.0, // Sensitivity for panning
             zoom_sensitivity: 0.1,
-            enabled: true,
+            min_radius: 0.1, // Very zoomed in
+            max_radius: 10.0, // Very zoomed out
+            enabled: active,
         },
+        EditorCamera2d,
+        target,
+        layers,
         EditorEntity, // Mark as editor entity
         Name::new("Editor Camera"), // Give it a name for debugging
+    )).id()
+}
+
+/// Spawn a viewport's perspective camera for 3D mode
+pub(crate) fn spawn_editor_camera_3d(
+    commands: &mut Commands,
+    target: RenderTarget,
+    layers: RenderLayers,
+    active: bool,
+) -> Entity {
+    commands.spawn((
+        Camera3d::default(),
+        Camera {
+            is_active: active,
+            ..default()
+        },
+        Projection::from(PerspectiveProjection::default()),
+        EditorCamera {
+            enabled: active,
+            ..default()
+        },
+        EditorCamera3d,
+        target,
+        layers,
+        EditorEntity,
+        Name::new("Editor Camera 3D"),
+    )).id()
+}
+
+/// Spawn the camera that draws the editor UI (and nothing else) to the window
+fn spawn_ui_camera(mut commands: Commands) {
+    commands.spawn((
+        Camera2d,
+        Camera {
+            // After the viewport textures are rendered
+            order: 1,
+            ..default()
+        },
+        RenderLayers::none(),
+        IsDefaultUiCamera,
+        EditorEntity,
+        Name::new("Editor UI Camera"),
     ));
 }
 
+/// Activate the camera matching the viewport mode
+fn switch_viewport_mode(
+    mode: Res<ViewportMode>,
+    mut cameras: Query<(&mut Camera, &mut EditorCamera, Has<EditorCamera3d>)>,
+) {
+    if !mode.is_changed() || mode.is_added() {
+        return;
+    }
+
+    for (mut camera, mut editor_camera, is_3d) in &mut cameras {
+        let active = is_3d == (*mode == ViewportMode::ThreeD);
+        camera.is_active = active;
+        editor_camera.enabled = active;
+    }
+
+    info!("Switched viewport to {:?}", *mode);
+}
+
 /// Handle orbit controls (right mouse button + drag)
 fn editor_camera_orbit(
     mouse_button: Res<ButtonInput<MouseButton>>,
     mut motion_events: MessageReader<MouseMotion>,
+    mode: Res<ViewportMode>,
+    active_viewport: Res<ActiveViewport>,
+    viewports: Query<&ViewportCameras>,
     mut query: Query<&mut EditorCamera>,
-    ui_query: Query<&Interaction>,
 ) {
     if !mouse_button.pressed(MouseButton::Right) {
         return;
     }
 
-    // Check if mouse is over any UI element
-    let mouse_over_ui = ui_query.iter().any(|interaction| {
-        matches!(interaction, Interaction::Hovered | Interaction::Pressed)
-    });
-
-    // Don't process camera orbit if mouse is over UI
-    if mouse_over_ui {
+    // Only the camera of the viewport under the cursor moves
+    let Some(entity) = active_viewport.hovered_camera(&viewports, *mode) else {
         return;
-    }
-
-    for mut camera in &mut query {
-        if !camera.enabled {
-            continue;
-        }
+    };
 
+    if let Ok(mut camera) = query.get_mut(entity) && camera.enabled {
         for event in motion_events.read() {
             camera.yaw += event.delta.x * camera.orbit_sensitivity;
             camera.pitch += event.delta.y * camera.orbit_sensitivity;
@@ -122,28 +303,21 @@ fn editor_camera_orbit(
 fn editor_camera_pan(
     mouse_button: Res<ButtonInput<MouseButton>>,
     mut motion_events: MessageReader<MouseMotion>,
+    mode: Res<ViewportMode>,
+    active_viewport: Res<ActiveViewport>,
+    viewports: Query<&ViewportCameras>,
     mut query: Query<(&mut EditorCamera, &Transform)>,
-    ui_query: Query<&Interaction>,
 ) {
     if !mouse_button.pressed(MouseButton::Middle) {
         return;
     }
 
-    // Check if mouse is over any UI element
-    let mouse_over_ui = ui_query.iter().any(|interaction| {
-        matches!(interaction, Interaction::Hovered | Interaction::Pressed)
-    });
-
-    // Don't process camera pan if mouse is over UI
-    if mouse_over_ui {
+    // Only the camera of the viewport under the cursor moves
+    let Some(entity) = active_viewport.hovered_camera(&viewports, *mode) else {
         return;
-    }
-
-    for (mut camera, transform) in &mut query {
-        if !camera.enabled {
-            continue;
-        }
+    };
 
+    if let Ok((mut camera, transform)) = query.get_mut(entity) && camera.enabled {
         for event in motion_events.read() {
             // Pan in camera space
             let right = transform.right();
@@ -156,63 +330,313 @@ fn editor_camera_pan(
     }
 }
 
-/// Handle zoom controls (mouse wheel) - for 2D, this changes orthographic scale
+/// Handle zoom controls (mouse wheel) - for 2D, this changes orthographic scale, for 3D the orbit distance
 fn editor_camera_zoom(
     mut scroll_events: MessageReader<MouseWheel>,
+    mode: Res<ViewportMode>,
+    active_viewport: Res<ActiveViewport>,
+    viewports: Query<&ViewportCameras>,
     mut query: Query<&mut EditorCamera>,
-    ui_query: Query<&Interaction>,
 ) {
-    // Check if mouse is over any UI element
-    let mouse_over_ui = ui_query.iter().any(|interaction| {
-        matches!(interaction, Interaction::Hovered | Interaction::Pressed)
-    });
-
-    // Don't process camera zoom if mouse is over UI
-    if mouse_over_ui {
+    // Only the camera of the viewport under the cursor zooms (scrolling panels doesn't)
+    let Some(entity) = active_viewport.hovered_camera(&viewports, *mode) else {
+        scroll_events.clear();
         return;
-    }
-
-    for mut camera in &mut query {
-        if !camera.enabled {
-            continue;
-        }
+    };
 
+    if let Ok(mut camera) = query.get_mut(entity) && camera.enabled {
         for event in scroll_events.read() {
             // For 2D: radius is the orthographic scale
             // Negative Y scroll = zoom out (increase scale)
             // Positive Y scroll = zoom in (decrease scale)
             camera.radius -= event.y * camera.zoom_sensitivity * camera.radius * 0.1;
-            // Clamp zoom: 0.1 = very zoomed in, 10.0 = very zoomed out
-            camera.radius = camera.radius.clamp(0.1, 10.0);
+            camera.radius = camera.radius.clamp(camera.min_radius, camera.max_radius);
         }
     }
 }
 
 /// Update camera transform for 2D (pan and zoom via projection scale)
+///
+/// Only writes what differs, so a still camera isn't marked changed (the grid redraws on change).
 fn update_camera_transform(
-    mut query: Query<(&EditorCamera, &mut Transform, &mut Projection)>,
+    mut query: Query<(&EditorCamera, &mut Transform, &mut Projection), With<EditorCamera2d>>,
 ) {
     for (camera, mut transform, mut projection) in &mut query {
-        // For 2D camera: position at focus point
-        transform.translation = Vec3::new(
-            camera.focus.x,
-            camera.focus.y,
-            999.9, // Keep Z at standard 2D camera position
-        );
-
-        // Keep camera looking down the -Z axis (no rotation for 2D)
-        transform.rotation = Quat::IDENTITY;
+        // For 2D camera: position at focus point, looking down the -Z axis (no rotation for 2D)
+        transform.set_if_neq(Transform {
+            translation: Vec3::new(
+                camera.focus.x,
+                camera.focus.y,
+                999.9, // Keep Z at standard 2D camera position
+            ),
+            rotation: Quat::IDENTITY,
+            scale: transform.scale,
+        });
 
         // Update orthographic projection scale for zoom
         // radius field is repurposed as zoom scale for 2D
-        if let Projection::Orthographic(ortho) = projection.as_mut() {
+        let scale = match &*projection {
+            Projection::Orthographic(ortho) => Some(ortho.scale),
+            _ => None,
+        };
+        if scale.is_some_and(|scale| scale != camera.radius)
+            && let Projection::Orthographic(ortho) = projection.as_mut()
+        {
             ortho.scale = camera.radius;
         }
     }
 }
 
+/// Update the 3D camera transform: orbit `focus` at `radius`, rotated by yaw and pitch
+fn update_camera_transform_3d(
+    mut query: Query<(&EditorCamera, &mut Transform), With<EditorCamera3d>>,
+) {
+    for (camera, mut transform) in &mut query {
+        transform.set_if_neq(orbit_transform(camera));
+    }
+}
+
+/// Transform of a camera orbiting its focus (positive pitch looks down from above)
+pub fn orbit_transform(camera: &EditorCamera) -> Transform {
+    let rotation = Quat::from_euler(EulerRot::YXZ, camera.yaw, -camera.pitch, 0.0);
+    Transform {
+        translation: camera.focus + rotation * Vec3::Z * camera.radius,
+        rotation,
+        ..default()
+    }
+}
+
 /// Frame the camera on a specific point
 pub fn frame_camera_on_point(camera: &mut EditorCamera, point: Vec3, radius: f32) {
     camera.focus = point;
     camera.radius = radius;
 }
+
+/// F frames the selection, holding Shift+F follows it
+fn handle_frame_shortcuts(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    ui_focus: Res<UiFocus>,
+    mut follow: ResMut<CameraFollow>,
+    mut frame: MessageWriter<FrameSelection>,
+) {
+    let shift = keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+
+    // Following stops as soon as Shift or F is released
+    if follow.active && !(shift && keyboard.pressed(KeyCode::KeyF)) {
+        follow.active = false;
+        info!("Follow selection off");
+    }
+
+    if ui_focus.has_focus() || !keyboard.just_pressed(KeyCode::KeyF) {
+        return;
+    }
+
+    if shift {
+        follow.active = true;
+        info!("Follow selection on");
+    }
+
+    frame.write(FrameSelection);
+}
+
+/// World-space bounds of the selected entities as (min, max)
+///
+/// Sprites use their rotated sprite bounds, meshes their AABB, anything else a small box.
+fn selection_bounds(
+    selection: &EditorSelection,
+    query: &Query<(&GlobalTransform, Option<&Sprite>, Option<&Aabb>)>,
+) -> Option<(Vec3, Vec3)> {
+    selection
+        .selected()
+        .filter_map(|entity| query.get(entity).ok())
+        .map(|(global_transform, sprite, aabb)| {
+            let transform = global_transform.compute_transform();
+            if sprite.is_some() {
+                let rect = sprite_bounds(&transform, sprite);
+                let z = transform.translation.z;
+                (rect.min.extend(z), rect.max.extend(z))
+            } else if let Some(aabb) = aabb {
+                let (center, half) = (Vec3::from(aabb.center), Vec3::from(aabb.half_extents));
+                let corners = (0..8).map(|i| {
+                    let sign = Vec3::new(
+                        if i & 1 == 0 { -1.0 } else { 1.0 },
+                        if i & 2 == 0 { -1.0 } else { 1.0 },
+                        if i & 4 == 0 { -1.0 } else { 1.0 },
+                    );
+                    global_transform.transform_point(center + half * sign)
+                });
+                corners.fold((Vec3::MAX, Vec3::MIN), |(min, max), corner| (min.min(corner), max.max(corner)))
+            } else {
+                let half = Vec3::splat(POINT_BOUNDS_SIZE / 2.0);
+                (transform.translation - half, transform.translation + half)
+            }
+        })
+        .reduce(|(min, max), (other_min, other_max)| (min.min(other_min), max.max(other_max)))
+}
+
+/// Radius that fits bounds of the given size in a camera's view
+fn framing_radius(camera: &Camera, projection: &Projection, size: Vec3) -> f32 {
+    match projection {
+        // 2D: the orthographic scale that fits the bounds in the viewport
+        Projection::Orthographic(_) => {
+            let viewport = camera.logical_viewport_size().unwrap_or(Vec2::ONE).max(Vec2::ONE);
+            (size.truncate() * FRAME_MARGIN / viewport).max_element()
+        }
+        // 3D: the distance at which the bounding sphere fits the field of view
+        Projection::Perspective(perspective) => {
+            let sphere_radius = (size.length() / 2.0).max(POINT_BOUNDS_SIZE);
+            sphere_radius * FRAME_MARGIN / (perspective.fov / 2.0).sin()
+        }
+        Projection::Custom(_) => size.max_element() * FRAME_MARGIN,
+    }
+}
+
+/// Start a camera transition to the selection's bounds (from F or a hierarchy double-click)
+///
+/// Frames in the last hovered viewport.
+fn frame_selection(
+    mut requests: MessageReader<FrameSelection>,
+    selection: Res<EditorSelection>,
+    preferences: Res<EditorPreferences>,
+    mode: Res<ViewportMode>,
+    active_viewport: Res<ActiveViewport>,
+    viewports: Query<&ViewportCameras>,
+    bounds_query: Query<(&GlobalTransform, Option<&Sprite>, Option<&Aabb>)>,
+    cameras: Query<(&EditorCamera, &Camera, &Projection)>,
+    mut commands: Commands,
+) {
+    if requests.read().count() == 0 {
+        return;
+    }
+
+    let Some((min, max)) = selection_bounds(&selection, &bounds_query) else {
+        return;
+    };
+
+    let Some(entity) = active_viewport.camera(&viewports, *mode) else {
+        return;
+    };
+
+    if let Ok((editor_camera, camera, projection)) = cameras.get(entity) {
+        let radius = framing_radius(camera, projection, max - min)
+            .clamp(editor_camera.min_radius, editor_camera.max_radius);
+        let transition = CameraTransition::new(
+            editor_camera,
+            (min + max) / 2.0,
+            radius,
+            preferences.camera.frame_duration,
+        );
+        commands.entity(entity).insert(transition);
+    }
+}
+
+/// Keep the last hovered viewport's camera centered on the selection while following it
+fn follow_selection(
+    mut follow: ResMut<CameraFollow>,
+    selection: Res<EditorSelection>,
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    mode: Res<ViewportMode>,
+    active_viewport: Res<ActiveViewport>,
+    viewports: Query<&ViewportCameras>,
+    bounds_query: Query<(&GlobalTransform, Option<&Sprite>, Option<&Aabb>)>,
+    mut cameras: Query<(&mut EditorCamera, Option<&mut CameraTransition>)>,
+) {
+    if !follow.active {
+        return;
+    }
+
+    // Panning away or deselecting everything stops following
+    let bounds = selection_bounds(&selection, &bounds_query);
+    let Some((min, max)) = bounds.filter(|_| !mouse_button.pressed(MouseButton::Middle)) else {
+        follow.active = false;
+        return;
+    };
+    let center = (min + max) / 2.0;
+
+    let Some(entity) = active_viewport.camera(&viewports, *mode) else {
+        return;
+    };
+
+    if let Ok((mut editor_camera, transition)) = cameras.get_mut(entity) {
+        // Let a framing transition finish, but aim it at where the selection is now
+        match transition {
+            Some(mut transition) => transition.to_focus = center,
+            None => editor_camera.focus = center,
+        }
+    }
+}
+
+/// Ease `focus` and `radius` towards the transition target
+fn animate_camera_transition(
+    time: Res<Time<Real>>,
+    mut cameras: Query<(Entity, &mut EditorCamera, &mut CameraTransition)>,
+    mut commands: Commands,
+) {
+    for (entity, mut camera, mut transition) in &mut cameras {
+        transition.elapsed += time.delta_secs();
+
+        let progress = if transition.duration > 0.0 {
+            (transition.elapsed / transition.duration).min(1.0)
+        } else {
+            1.0
+        };
+        let t = EaseFunction::CubicInOut.sample_clamped(progress);
+
+        if progress >= 1.0 {
+            frame_camera_on_point(&mut camera, transition.to_focus, transition.to_radius);
+            commands.entity(entity).remove::<CameraTransition>();
+        } else {
+            camera.focus = transition.from_focus.lerp(transition.to_focus, t);
+            camera.radius = transition.from_radius.lerp(transition.to_radius, t);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GridConfig, draw_grids};
+
+    #[test]
+    fn still_camera_leaves_grid_alone() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, TransformPlugin))
+            .init_resource::<ViewportMode>()
+            .init_resource::<GridConfig>()
+            .init_resource::<Assets<GizmoAsset>>()
+            .add_systems(Update, (update_camera_transform, draw_grids).chain());
+
+        let camera_2d = app
+            .world_mut()
+            .spawn((
+                EditorCamera { radius: 1.0, ..default() },
+                EditorCamera2d,
+                Transform::from_xyz(0.0, 0.0, 999.9),
+                Projection::from(OrthographicProjection::default_2d()),
+            ))
+            .id();
+        let grid_gizmo = app.world_mut().resource_mut::<Assets<GizmoAsset>>().add(GizmoAsset::new());
+        app.world_mut().spawn(ViewportCameras {
+            camera_2d,
+            camera_3d: Entity::PLACEHOLDER,
+            grid: Entity::PLACEHOLDER,
+            grid_gizmo,
+            grid_layer: 2,
+        });
+
+        // The new viewport and the camera's first propagated transform draw the grid
+        app.update();
+        app.update();
+        let drawn = app.world().resource_ref::<Assets<GizmoAsset>>().last_changed();
+
+        app.update();
+        app.update();
+        assert_eq!(app.world().resource_ref::<Assets<GizmoAsset>>().last_changed(), drawn);
+
+        // Panning redraws it
+        app.world_mut().get_mut::<EditorCamera>(camera_2d).unwrap().focus.x = 10.0;
+        app.update();
+        app.update();
+        assert_ne!(app.world().resource_ref::<Assets<GizmoAsset>>().last_changed(), drawn);
+    }
+}
@@ -1,9 +1,18 @@
 //! Transform gizmos for moving, rotating, and scaling entities visually in the viewport
 //!
 //! Provides interactive handles for Move, Rotate, and Scale operations on selected entities.
+//! While dragging, typing a number applies an exact amount (Enter confirms, Esc cancels).
 
 use bevy::prelude::*;
-use bevy_editor_core::{EditorSelection, UiFocus};
+use bevy::input::ButtonState;
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::picking::Pickable;
+use bevy_editor_core::{EditorEntity, EditorPreferences, EditorSelection, EntityRemapped, UiFocus};
+use bevy_editor_undo::{CompositeCommand, SetTransformCommand, UndoCommandsExt};
+use crate::{
+    GizmoAxis3d, GizmoDrag3d, GridConfig, SmartGuides, SnapSteps, ViewportCursor,
+    snap_bounds_to_sprites, snap_value, sprite_bounds,
+};
 
 /// Active gizmo mode
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Resource)]
@@ -32,6 +41,61 @@ impl Default for GizmoSpace {
     }
 }
 
+impl GizmoSpace {
+    /// The other space, for toggling with a shortcut or toolbar button
+    pub fn toggled(self) -> Self {
+        match self {
+            Self::Local => Self::World,
+            Self::World => Self::Local,
+        }
+    }
+
+    /// Orientation of the gizmo axes for an entity: its own rotation in Local space,
+    /// screen-aligned in World space
+    pub fn axes_rotation(self, transform: &Transform) -> Rot2 {
+        match self {
+            Self::Local => Rot2::radians(transform.rotation.to_euler(bevy::math::EulerRot::XYZ).2),
+            Self::World => Rot2::IDENTITY,
+        }
+    }
+}
+
+/// Scale factor along an entity's own axes for scaling by `factor` along the gizmo `axes`
+///
+/// A rotated entity can't be stretched along other axes without shearing, so each local axis
+/// grows by as much as the gizmo-axis scaling lengthens it (exact when the axes line up).
+pub(crate) fn local_scale_factor(rotation: Quat, axes: Quat, factor: Vec3) -> Vec3 {
+    let relative = axes.inverse() * rotation;
+    Vec3::new(
+        (factor * (relative * Vec3::X)).length(),
+        (factor * (relative * Vec3::Y)).length(),
+        (factor * (relative * Vec3::Z)).length(),
+    )
+}
+
+/// Point that rotate and scale operations pivot around when several entities are selected
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Resource)]
+pub enum GizmoPivot {
+    /// Average position of the selected entities
+    #[default]
+    Median,
+    /// Position of the primary selection
+    Primary,
+    /// Each entity rotates and scales around its own origin
+    IndividualOrigins,
+}
+
+impl GizmoPivot {
+    /// Next pivot mode, for cycling with a shortcut
+    pub fn next(self) -> Self {
+        match self {
+            Self::Median => Self::Primary,
+            Self::Primary => Self::IndividualOrigins,
+            Self::IndividualOrigins => Self::Median,
+        }
+    }
+}
+
 /// Axis for move and scale gizmos
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum GizmoAxis {
@@ -40,29 +104,93 @@ pub enum GizmoAxis {
     XY, // For center handle or free movement
 }
 
+/// Length of the move gizmo arrows
+const ARROW_LENGTH: f32 = 50.0;
+const ARROW_HEAD_SIZE: f32 = 10.0;
+/// Size of the center square of the move gizmo
+const CENTER_SIZE: f32 = 8.0;
+/// Radius of the rotate gizmo ring
+const CIRCLE_RADIUS: f32 = 50.0;
+/// Size of the scale gizmo handles
+const HANDLE_SIZE: f32 = 8.0;
+/// Extra distance around a handle that still counts as hitting it (world units)
+const HIT_TOLERANCE: f32 = 5.0;
+
+/// Color of the handle under the cursor (or being dragged)
+const HIGHLIGHT_COLOR: Color = Color::srgb(1.0, 0.6, 0.1);
+
+/// Scale range a scale drag is clamped to (shared by the 2D and 3D gizmos)
+pub(crate) const MIN_GIZMO_SCALE: f32 = 0.01;
+pub(crate) const MAX_GIZMO_SCALE: f32 = 100.0;
+
+/// An entity being transformed by the current drag
+#[derive(Debug, Clone, Copy)]
+pub struct GizmoDragTarget {
+    pub entity: Entity,
+    /// Transform when the drag started (used to compute the new transform, and to cancel)
+    pub initial_transform: Transform,
+}
+
 /// State tracking current gizmo drag operation
 #[derive(Resource, Default)]
 pub struct GizmoDragState {
     /// Whether we're currently dragging
     pub is_dragging: bool,
-    /// The entity being transformed
-    pub target_entity: Option<Entity>,
+    /// The entities being transformed
+    pub targets: Vec<GizmoDragTarget>,
     /// Initial mouse position in world space when drag started
     pub drag_start_world_pos: Vec2,
-    /// Initial transform when drag started
-    pub initial_transform: Option<Transform>,
+    /// Gizmo center when the drag started (the pivot, unless using individual origins)
+    pub pivot_point: Vec2,
+    /// Orientation of the gizmo axes when the drag started
+    pub axes_rotation: Rot2,
     /// Which axis is being dragged
     pub drag_axis: Option<GizmoAxis>,
+    /// Handle under the cursor while not dragging
+    pub hovered_axis: Option<GizmoAxis>,
+    /// Id of the current drag, used to merge its per-frame transform commands
+    pub drag_id: u64,
+    /// Value typed during the drag, overriding the mouse (e.g. "10", "-45", "2.5" or "10,5")
+    pub numeric_input: Option<String>,
+    /// How far the drag has gone, for the readout
+    pub amount: Option<GizmoDragAmount>,
+    /// Handle and reference points of a drag in 3D mode
+    pub drag_3d: Option<GizmoDrag3d>,
+    /// 3D gizmo handle under the cursor while not dragging
+    pub hovered_axis_3d: Option<GizmoAxis3d>,
+}
+
+/// How far a drag has gone, along the gizmo axes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GizmoDragAmount {
+    /// Offset along the gizmo axes
+    Translate(Vec2),
+    /// Angle in radians
+    Rotate(f32),
+    /// Scale factor along the gizmo axes
+    Scale(Vec2),
+    /// Offset in world space (3D mode)
+    Translate3d(Vec3),
+    /// Scale factor along the entity's local axes (3D mode)
+    Scale3d(Vec3),
 }
 
+/// Marker for the text showing the drag amount next to the cursor
+#[derive(Component)]
+pub struct GizmoReadout;
+
 /// Keyboard shortcut handler for switching gizmo modes
 pub fn handle_gizmo_mode_shortcuts(
     keyboard: Res<ButtonInput<KeyCode>>,
     ui_focus: Res<UiFocus>,
     mut gizmo_mode: ResMut<GizmoMode>,
+    mut gizmo_pivot: ResMut<GizmoPivot>,
+    mut gizmo_space: ResMut<GizmoSpace>,
+    drag_state: Res<GizmoDragState>,
 ) {
-    // Only handle shortcuts when UI doesn't have focus
-    if ui_focus.focused_entity.is_some() {
+    // Only handle shortcuts when UI doesn't have focus (or a drag is taking typed input),
+    // and don't switch modes mid-drag
+    if ui_focus.has_focus() || drag_state.is_dragging {
         return;
     }
 
@@ -76,77 +204,175 @@ pub fn handle_gizmo_mode_shortcuts(
         *gizmo_mode = GizmoMode::Scale;
         info!("Switched to Scale mode (R)");
     }
+
+    if keyboard.just_pressed(KeyCode::Period) {
+        *gizmo_pivot = gizmo_pivot.next();
+        info!("Switched pivot to {:?} (.)", *gizmo_pivot);
+    }
+
+    if keyboard.just_pressed(KeyCode::KeyT) {
+        *gizmo_space = gizmo_space.toggled();
+        info!("Switched to {:?} space (T)", *gizmo_space);
+    }
 }
 
-/// Draw gizmos for the currently selected entity
+/// Selected entities the gizmo acts on, with their current transforms
+///
+/// Entities whose ancestor is also selected are skipped, they already move with their parent.
+pub fn gizmo_targets(
+    selection: &EditorSelection,
+    transforms: &Query<&Transform>,
+    parents: &Query<&ChildOf>,
+) -> Vec<(Entity, Transform)> {
+    selection
+        .selected()
+        .filter(|entity| !parents.iter_ancestors(*entity).any(|ancestor| selection.is_selected(ancestor)))
+        .filter_map(|entity| transforms.get(entity).ok().map(|transform| (entity, *transform)))
+        .collect()
+}
+
+/// Where the gizmo is drawn for the given targets
+///
+/// This is the pivot point, except for individual origins where the gizmo sits at the median.
+pub fn gizmo_center(
+    pivot: GizmoPivot,
+    primary: Option<Entity>,
+    targets: &[(Entity, Transform)],
+) -> Option<Vec2> {
+    if targets.is_empty() {
+        return None;
+    }
+
+    if pivot == GizmoPivot::Primary
+        && let Some((_, transform)) = targets.iter().find(|(entity, _)| Some(*entity) == primary)
+    {
+        return Some(transform.translation.truncate());
+    }
+
+    let sum: Vec2 = targets.iter().map(|(_, transform)| transform.translation.truncate()).sum();
+    Some(sum / targets.len() as f32)
+}
+
+/// Transform the gizmo is drawn with: placed at the gizmo center, oriented and sized
+/// like the primary selection
+fn gizmo_transform(
+    pivot: GizmoPivot,
+    primary: Option<Entity>,
+    targets: &[(Entity, Transform)],
+) -> Option<Transform> {
+    let center = gizmo_center(pivot, primary, targets)?;
+    let (_, reference) = targets
+        .iter()
+        .find(|(entity, _)| Some(*entity) == primary)
+        .or(targets.first())?;
+
+    Some(Transform {
+        translation: center.extend(reference.translation.z),
+        ..*reference
+    })
+}
+
+/// Draw gizmos for the currently selected entities
 pub fn draw_gizmos(
     selection: Res<EditorSelection>,
     gizmo_mode: Res<GizmoMode>,
+    gizmo_pivot: Res<GizmoPivot>,
+    gizmo_space: Res<GizmoSpace>,
+    drag_state: Res<GizmoDragState>,
     transforms: Query<&Transform>,
+    parents: Query<&ChildOf>,
     mut gizmos: Gizmos,
 ) {
-    // Only draw if exactly one entity is selected
-    let Some(selected_entity) = selection.selected().next() else {
+    let targets = gizmo_targets(&selection, &transforms, &parents);
+    let Some(transform) = gizmo_transform(*gizmo_pivot, selection.primary(), &targets) else {
         return;
     };
 
-    let Ok(transform) = transforms.get(selected_entity) else {
-        return;
+    // Highlight the dragged handle, or the hovered one when not dragging
+    let highlight = if drag_state.is_dragging {
+        drag_state.drag_axis
+    } else {
+        drag_state.hovered_axis
     };
+    let rotation = gizmo_space.axes_rotation(&transform);
 
     match *gizmo_mode {
-        GizmoMode::Translate => draw_move_gizmo(&mut gizmos, transform),
-        GizmoMode::Rotate => draw_rotate_gizmo(&mut gizmos, transform),
-        GizmoMode::Scale => draw_scale_gizmo(&mut gizmos, transform),
+        GizmoMode::Translate => draw_move_gizmo(&mut gizmos, &transform, rotation, highlight),
+        GizmoMode::Rotate => draw_rotate_gizmo(&mut gizmos, &transform, highlight),
+        GizmoMode::Scale => draw_scale_gizmo(&mut gizmos, &transform, rotation, highlight),
+    }
+
+    // Mark the origins each entity rotates and scales around
+    if *gizmo_pivot == GizmoPivot::IndividualOrigins && targets.len() > 1 {
+        for (_, target) in &targets {
+            gizmos.circle_2d(target.translation.truncate(), 3.0, Color::srgb(1.0, 1.0, 1.0));
+        }
     }
 }
 
-/// Draw the move gizmo (X and Y axis arrows)
-fn draw_move_gizmo(gizmos: &mut Gizmos, transform: &Transform) {
+/// Pick the handle color, using the highlight color if it's the highlighted handle
+fn handle_color(color: Color, axis: GizmoAxis, highlight: Option<GizmoAxis>) -> Color {
+    if highlight == Some(axis) {
+        HIGHLIGHT_COLOR
+    } else {
+        color
+    }
+}
+
+/// Draw the move gizmo (X and Y axis arrows, oriented by `rotation`)
+fn draw_move_gizmo(gizmos: &mut Gizmos, transform: &Transform, rotation: Rot2, highlight: Option<GizmoAxis>) {
     let pos = transform.translation.truncate();
-    const ARROW_LENGTH: f32 = 50.0;
-    const ARROW_HEAD_SIZE: f32 = 10.0;
+    // Gizmo-space offset to world position
+    let at = |offset: Vec2| pos + rotation * offset;
 
     // X axis (red arrow pointing right)
-    gizmos.line_2d(pos, pos + Vec2::new(ARROW_LENGTH, 0.0), Color::srgb(1.0, 0.0, 0.0));
+    let x_color = handle_color(Color::srgb(1.0, 0.0, 0.0), GizmoAxis::X, highlight);
+    gizmos.line_2d(pos, at(Vec2::new(ARROW_LENGTH, 0.0)), x_color);
     // Arrow head
     gizmos.line_2d(
-        pos + Vec2::new(ARROW_LENGTH, 0.0),
-        pos + Vec2::new(ARROW_LENGTH - ARROW_HEAD_SIZE, ARROW_HEAD_SIZE / 2.0),
-        Color::srgb(1.0, 0.0, 0.0),
+        at(Vec2::new(ARROW_LENGTH, 0.0)),
+        at(Vec2::new(ARROW_LENGTH - ARROW_HEAD_SIZE, ARROW_HEAD_SIZE / 2.0)),
+        x_color,
     );
     gizmos.line_2d(
-        pos + Vec2::new(ARROW_LENGTH, 0.0),
-        pos + Vec2::new(ARROW_LENGTH - ARROW_HEAD_SIZE, -ARROW_HEAD_SIZE / 2.0),
-        Color::srgb(1.0, 0.0, 0.0),
+        at(Vec2::new(ARROW_LENGTH, 0.0)),
+        at(Vec2::new(ARROW_LENGTH - ARROW_HEAD_SIZE, -ARROW_HEAD_SIZE / 2.0)),
+        x_color,
     );
 
     // Y axis (green arrow pointing up)
-    gizmos.line_2d(pos, pos + Vec2::new(0.0, ARROW_LENGTH), Color::srgb(0.0, 1.0, 0.0));
+    let y_color = handle_color(Color::srgb(0.0, 1.0, 0.0), GizmoAxis::Y, highlight);
+    gizmos.line_2d(pos, at(Vec2::new(0.0, ARROW_LENGTH)), y_color);
     // Arrow head
     gizmos.line_2d(
-        pos + Vec2::new(0.0, ARROW_LENGTH),
-        pos + Vec2::new(ARROW_HEAD_SIZE / 2.0, ARROW_LENGTH - ARROW_HEAD_SIZE),
-        Color::srgb(0.0, 1.0, 0.0),
+        at(Vec2::new(0.0, ARROW_LENGTH)),
+        at(Vec2::new(ARROW_HEAD_SIZE / 2.0, ARROW_LENGTH - ARROW_HEAD_SIZE)),
+        y_color,
     );
     gizmos.line_2d(
-        pos + Vec2::new(0.0, ARROW_LENGTH),
-        pos + Vec2::new(-ARROW_HEAD_SIZE / 2.0, ARROW_LENGTH - ARROW_HEAD_SIZE),
-        Color::srgb(0.0, 1.0, 0.0),
+        at(Vec2::new(0.0, ARROW_LENGTH)),
+        at(Vec2::new(-ARROW_HEAD_SIZE / 2.0, ARROW_LENGTH - ARROW_HEAD_SIZE)),
+        y_color,
     );
 
     // Center handle (white square for XY movement)
-    const CENTER_SIZE: f32 = 8.0;
-    gizmos.rect_2d(pos, Vec2::splat(CENTER_SIZE), Color::srgb(1.0, 1.0, 1.0));
+    gizmos.rect_2d(
+        Isometry2d::new(pos, rotation),
+        Vec2::splat(CENTER_SIZE),
+        handle_color(Color::srgb(1.0, 1.0, 1.0), GizmoAxis::XY, highlight),
+    );
 }
 
 /// Draw the rotate gizmo (circular handle)
-fn draw_rotate_gizmo(gizmos: &mut Gizmos, transform: &Transform) {
+fn draw_rotate_gizmo(gizmos: &mut Gizmos, transform: &Transform, highlight: Option<GizmoAxis>) {
     let pos = transform.translation.truncate();
-    const CIRCLE_RADIUS: f32 = 50.0;
 
     // Draw circle
-    gizmos.circle_2d(pos, CIRCLE_RADIUS, Color::srgb(0.3, 0.6, 1.0));
+    gizmos.circle_2d(
+        pos,
+        CIRCLE_RADIUS,
+        handle_color(Color::srgb(0.3, 0.6, 1.0), GizmoAxis::XY, highlight),
+    );
 
     // Draw rotation indicator (small line from center, rotated with entity)
     let rotation_z = transform.rotation.to_euler(bevy::math::EulerRot::XYZ).2;
@@ -157,199 +383,718 @@ fn draw_rotate_gizmo(gizmos: &mut Gizmos, transform: &Transform) {
     gizmos.line_2d(pos, indicator_end, Color::srgb(1.0, 1.0, 1.0));
 }
 
-/// Draw the scale gizmo (corner handles that scale with entity)
-fn draw_scale_gizmo(gizmos: &mut Gizmos, transform: &Transform) {
+/// Distance of the scale gizmo handles from its center (grows with the entity's scale)
+fn scale_handle_distance(transform: &Transform) -> f32 {
+    let avg_scale = (transform.scale.x + transform.scale.y) / 2.0;
+    40.0 * avg_scale.max(0.5)
+}
+
+/// Handles of the scale gizmo as (offset from center, axis): uniform corners, then per-axis edges
+fn scale_handles(distance: f32) -> [(Vec2, GizmoAxis); 8] {
+    [
+        (Vec2::new(distance, distance), GizmoAxis::XY),   // Top-right
+        (Vec2::new(-distance, distance), GizmoAxis::XY),  // Top-left
+        (Vec2::new(-distance, -distance), GizmoAxis::XY), // Bottom-left
+        (Vec2::new(distance, -distance), GizmoAxis::XY),  // Bottom-right
+        (Vec2::new(distance, 0.0), GizmoAxis::X),         // Right
+        (Vec2::new(-distance, 0.0), GizmoAxis::X),        // Left
+        (Vec2::new(0.0, distance), GizmoAxis::Y),         // Top
+        (Vec2::new(0.0, -distance), GizmoAxis::Y),        // Bottom
+    ]
+}
+
+/// Draw the scale gizmo (corner handles that scale with entity, edge handles for a single axis)
+fn draw_scale_gizmo(gizmos: &mut Gizmos, transform: &Transform, rotation: Rot2, highlight: Option<GizmoAxis>) {
     let pos = transform.translation.truncate();
 
     // Scale the gizmo handles with the entity for better visual feedback
-    let avg_scale = (transform.scale.x + transform.scale.y) / 2.0;
-    let scaled_distance = 40.0 * avg_scale.max(0.5);
-    const HANDLE_SIZE: f32 = 8.0;
-
-    // Four corner handles
-    let corners = [
-        Vec2::new(scaled_distance, scaled_distance),   // Top-right
-        Vec2::new(-scaled_distance, scaled_distance),  // Top-left
-        Vec2::new(-scaled_distance, -scaled_distance), // Bottom-left
-        Vec2::new(scaled_distance, -scaled_distance),  // Bottom-right
-    ];
-
-    for corner in corners {
-        let handle_pos = pos + corner;
+    let scaled_distance = scale_handle_distance(transform);
+
+    for (offset, axis) in scale_handles(scaled_distance) {
+        let color = match axis {
+            GizmoAxis::X => Color::srgb(1.0, 0.0, 0.0),
+            GizmoAxis::Y => Color::srgb(0.0, 1.0, 0.0),
+            GizmoAxis::XY => Color::srgb(1.0, 1.0, 0.0),
+        };
         gizmos.rect_2d(
-            handle_pos,
+            Isometry2d::new(pos + rotation * offset, rotation),
             Vec2::splat(HANDLE_SIZE),
-            Color::srgb(1.0, 1.0, 0.0),
+            handle_color(color, axis, highlight),
         );
     }
 
     // Draw box outline (scales with entity)
     gizmos.rect_2d(
-        pos,
+        Isometry2d::new(pos, rotation),
         Vec2::splat(scaled_distance * 2.0),
         Color::srgb(0.5, 0.5, 0.5),
     );
 }
 
-/// Handle mouse down on gizmo handles to start dragging
-pub fn handle_gizmo_drag_start(
-    mouse_button: Res<ButtonInput<MouseButton>>,
-    windows: Query<&Window>,
-    camera_q: Query<(&Camera, &GlobalTransform)>,
+/// Find the gizmo handle under a world position, for a gizmo whose axes are oriented by `rotation`
+pub fn gizmo_hit_test(
+    gizmo_mode: GizmoMode,
+    transform: &Transform,
+    rotation: Rot2,
+    world_pos: Vec2,
+) -> Option<GizmoAxis> {
+    // Position relative to the gizmo, along its axes
+    let local = rotation.inverse() * (world_pos - transform.translation.truncate());
+
+    match gizmo_mode {
+        GizmoMode::Translate => {
+            // Center square first, it overlaps the start of both arrows
+            let center_extent = CENTER_SIZE / 2.0 + HIT_TOLERANCE;
+            if local.x.abs() <= center_extent && local.y.abs() <= center_extent {
+                return Some(GizmoAxis::XY);
+            }
+
+            let arrow_width = ARROW_HEAD_SIZE / 2.0 + HIT_TOLERANCE;
+            let along_arrow = |along: f32, across: f32| {
+                (0.0..=ARROW_LENGTH + HIT_TOLERANCE).contains(&along) && across.abs() <= arrow_width
+            };
+            if along_arrow(local.x, local.y) {
+                Some(GizmoAxis::X)
+            } else if along_arrow(local.y, local.x) {
+                Some(GizmoAxis::Y)
+            } else {
+                None
+            }
+        }
+        GizmoMode::Rotate => {
+            // Only the ring itself is a handle
+            ((local.length() - CIRCLE_RADIUS).abs() <= HIT_TOLERANCE).then_some(GizmoAxis::XY)
+        }
+        GizmoMode::Scale => {
+            let extent = HANDLE_SIZE / 2.0 + HIT_TOLERANCE;
+            scale_handles(scale_handle_distance(transform))
+                .into_iter()
+                .find(|(offset, _)| {
+                    let delta = local - *offset;
+                    delta.x.abs() <= extent && delta.y.abs() <= extent
+                })
+                .map(|(_, axis)| axis)
+        }
+    }
+}
+
+/// Cursor position in world space, if the cursor is over the viewport
+pub(crate) fn cursor_world_position(cursor: &ViewportCursor) -> Option<Vec2> {
+    let (camera, camera_transform) = cursor.camera_2d()?;
+    let cursor_pos = cursor.position(camera)?;
+    camera.viewport_to_world_2d(camera_transform, cursor_pos).ok()
+}
+
+/// Size of a screen pixel in world units, in the active viewport
+fn world_units_per_pixel(cursor: &ViewportCursor) -> f32 {
+    let Some((camera, camera_transform)) = cursor.camera_2d() else {
+        return 1.0;
+    };
+
+    match (
+        camera.viewport_to_world_2d(camera_transform, Vec2::ZERO),
+        camera.viewport_to_world_2d(camera_transform, Vec2::X),
+    ) {
+        (Ok(origin), Ok(one_pixel)) => origin.distance(one_pixel),
+        _ => 1.0,
+    }
+}
+
+/// Track which gizmo handle is under the cursor, for highlighting
+pub fn update_gizmo_hover(
+    cursor: ViewportCursor,
     gizmo_mode: Res<GizmoMode>,
+    gizmo_pivot: Res<GizmoPivot>,
+    gizmo_space: Res<GizmoSpace>,
     mut drag_state: ResMut<GizmoDragState>,
     selection: Res<EditorSelection>,
     transforms: Query<&Transform>,
+    parents: Query<&ChildOf>,
 ) {
-    if !mouse_button.just_pressed(MouseButton::Left) {
+    if drag_state.is_dragging {
         return;
     }
 
-    // Get mouse position in world space
-    let Ok(window) = windows.single() else {
-        return;
-    };
-    let Some(cursor_pos) = window.cursor_position() else {
-        return;
+    let targets = gizmo_targets(&selection, &transforms, &parents);
+    let hovered = gizmo_transform(*gizmo_pivot, selection.primary(), &targets)
+        .zip(cursor_world_position(&cursor))
+        .and_then(|(transform, world_pos)| {
+            gizmo_hit_test(*gizmo_mode, &transform, gizmo_space.axes_rotation(&transform), world_pos)
+        });
+
+    // Avoid change detection churn while the cursor moves over empty space
+    if drag_state.hovered_axis != hovered {
+        drag_state.hovered_axis = hovered;
+    }
+}
+
+/// Undo label for a drag of `count` entities
+pub(crate) fn drag_label(gizmo_mode: GizmoMode, count: usize) -> String {
+    let action = match gizmo_mode {
+        GizmoMode::Translate => "Move",
+        GizmoMode::Rotate => "Rotate",
+        GizmoMode::Scale => "Scale",
     };
 
-    let Ok((camera, camera_transform)) = camera_q.single() else {
+    if count == 1 {
+        format!("{action} Entity")
+    } else {
+        format!("{action} {count} Entities")
+    }
+}
+
+/// Handle mouse down on gizmo handles to start dragging
+pub fn handle_gizmo_drag_start(
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    cursor: ViewportCursor,
+    gizmo_mode: Res<GizmoMode>,
+    gizmo_pivot: Res<GizmoPivot>,
+    gizmo_space: Res<GizmoSpace>,
+    mut drag_state: ResMut<GizmoDragState>,
+    selection: Res<EditorSelection>,
+    transforms: Query<&Transform>,
+    parents: Query<&ChildOf>,
+    mut commands: Commands,
+) {
+    // A drag taking numeric input continues after the mouse is released
+    if !mouse_button.just_pressed(MouseButton::Left) || drag_state.is_dragging {
         return;
-    };
+    }
 
-    let Ok(world_pos) = camera.viewport_to_world_2d(camera_transform, cursor_pos) else {
+    // Get mouse position in world space
+    let Some(world_pos) = cursor_world_position(&cursor) else {
         return;
     };
 
     // Check if clicking on a gizmo handle
-    let Some(selected_entity) = selection.selected().next() else {
+    let targets = gizmo_targets(&selection, &transforms, &parents);
+    let Some(transform) = gizmo_transform(*gizmo_pivot, selection.primary(), &targets) else {
         return;
     };
 
-    let Ok(transform) = transforms.get(selected_entity) else {
+    let rotation = gizmo_space.axes_rotation(&transform);
+    let Some(axis) = gizmo_hit_test(*gizmo_mode, &transform, rotation, world_pos) else {
         return;
     };
 
-    let entity_pos = transform.translation.truncate();
+    drag_state.is_dragging = true;
+    drag_state.drag_id += 1;
+    drag_state.targets = targets
+        .iter()
+        .map(|(entity, transform)| GizmoDragTarget {
+            entity: *entity,
+            initial_transform: *transform,
+        })
+        .collect();
+    drag_state.drag_start_world_pos = world_pos;
+    drag_state.pivot_point = transform.translation.truncate();
+    drag_state.axes_rotation = rotation;
+    drag_state.drag_axis = Some(axis);
+    drag_state.numeric_input = None;
+    drag_state.amount = None;
 
-    // Hit test radius should scale with the entity's scale for better UX
-    let avg_scale = (transform.scale.x + transform.scale.y) / 2.0;
-    let hit_radius = 50.0 * avg_scale.max(0.5); // Scale with entity, minimum 0.5x
-
-    let distance = world_pos.distance(entity_pos);
-
-    // Hit test for gizmo (scales with entity size)
-    if distance < hit_radius {
-        drag_state.is_dragging = true;
-        drag_state.target_entity = Some(selected_entity);
-        drag_state.drag_start_world_pos = world_pos;
-        drag_state.initial_transform = Some(*transform);
-        drag_state.drag_axis = Some(GizmoAxis::XY);
-
-        match *gizmo_mode {
-            GizmoMode::Translate => info!("Started translating entity"),
-            GizmoMode::Rotate => info!("Started rotating entity"),
-            GizmoMode::Scale => info!("Started scaling entity"),
-        }
+    // The whole drag is one undo entry, which Esc rolls back
+    commands.begin_undo_transaction(drag_label(*gizmo_mode, targets.len()));
+
+    match *gizmo_mode {
+        GizmoMode::Translate => info!("Started translating {} entities along {:?}", targets.len(), axis),
+        GizmoMode::Rotate => info!("Started rotating {} entities", targets.len()),
+        GizmoMode::Scale => info!("Started scaling {} entities along {:?}", targets.len(), axis),
     }
 }
 
-/// Handle mouse drag to update entity transform
+/// Handle mouse drag (or typed values) to update the transforms of the dragged entities
 pub fn handle_gizmo_drag(
     mouse_button: Res<ButtonInput<MouseButton>>,
-    windows: Query<&Window>,
-    camera_q: Query<(&Camera, &GlobalTransform)>,
+    cursor: ViewportCursor,
     gizmo_mode: Res<GizmoMode>,
-    drag_state: Res<GizmoDragState>,
-    mut transforms: Query<&mut Transform>,
+    gizmo_pivot: Res<GizmoPivot>,
+    mut drag_state: ResMut<GizmoDragState>,
+    transforms: Query<&Transform>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    preferences: Res<EditorPreferences>,
+    grid: Res<GridConfig>,
+    sprites: Query<(Entity, &Transform, &Sprite), Without<EditorEntity>>,
+    parents: Query<&ChildOf>,
+    mut guides: ResMut<SmartGuides>,
+    mut commands: Commands,
 ) {
-    if !drag_state.is_dragging || !mouse_button.pressed(MouseButton::Left) {
+    let typing = drag_state.numeric_input.is_some();
+    if !drag_state.is_dragging || (!mouse_button.pressed(MouseButton::Left) && !typing) {
         return;
     }
 
-    let Some(target_entity) = drag_state.target_entity else {
+    if drag_state.targets.is_empty() {
         return;
-    };
+    }
 
-    let Some(initial_transform) = drag_state.initial_transform else {
-        return;
-    };
+    let axis = drag_state.drag_axis.unwrap_or(GizmoAxis::XY);
 
-    // Get current mouse position in world space
-    let Ok(window) = windows.single() else {
-        return;
+    // Typed values are exact, the mouse snaps
+    let (amount, snap) = if let Some(input) = &drag_state.numeric_input {
+        (Some(typed_drag_amount(*gizmo_mode, axis, input)), SnapSteps::default())
+    } else {
+        // Get current mouse position in world space
+        let Some(current_world_pos) = cursor_world_position(&cursor) else {
+            return;
+        };
+
+        let snap = SnapSteps::active(&preferences.snap, cursor.grid(&grid), &keyboard);
+        (mouse_drag_amount(*gizmo_mode, &drag_state, current_world_pos, snap), snap)
     };
-    let Some(cursor_pos) = window.cursor_position() else {
+    drag_state.amount = amount;
+    let Some(amount) = amount else {
         return;
     };
 
-    let Ok((camera, camera_transform)) = camera_q.single() else {
-        return;
-    };
+    let mut new_transforms: Vec<(Entity, Transform, Transform)> = drag_state
+        .targets
+        .iter()
+        .filter_map(|target| {
+            let current_transform = *transforms.get(target.entity).ok()?;
+            let transform = apply_drag_amount(
+                amount,
+                *gizmo_pivot,
+                &drag_state,
+                target.initial_transform,
+                current_transform,
+            );
+            Some((target.entity, current_transform, transform))
+        })
+        .collect();
 
-    let Ok(current_world_pos) = camera.viewport_to_world_2d(camera_transform, cursor_pos) else {
-        return;
-    };
+    // Line the dragged sprites up with the other sprites
+    guides.lines.clear();
+    if *gizmo_mode == GizmoMode::Translate
+        && let Some(threshold_px) = snap.sprite_threshold_px
+    {
+        let is_dragged = |entity: Entity| {
+            drag_state.targets.iter().any(|target| {
+                target.entity == entity || parents.iter_ancestors(entity).any(|ancestor| ancestor == target.entity)
+            })
+        };
+        let moving = new_transforms
+            .iter()
+            .filter_map(|(entity, _, transform)| {
+                let (_, _, sprite) = sprites.get(*entity).ok()?;
+                Some(sprite_bounds(transform, Some(sprite)))
+            })
+            .reduce(|bounds, other| bounds.union(other));
+        let others: Vec<Rect> = sprites
+            .iter()
+            .filter(|(entity, _, _)| !is_dragged(*entity))
+            .map(|(_, transform, sprite)| sprite_bounds(transform, Some(sprite)))
+            .collect();
+
+        // Only the free axes may move; rotated (local) axes don't line up with sprite bounds
+        let (snap_x, snap_y) = match axis {
+            GizmoAxis::XY => (true, true),
+            GizmoAxis::X => (drag_state.axes_rotation == Rot2::IDENTITY, false),
+            GizmoAxis::Y => (false, drag_state.axes_rotation == Rot2::IDENTITY),
+        };
 
-    // Apply transform based on gizmo mode
-    let Ok(mut transform) = transforms.get_mut(target_entity) else {
+        if let Some(moving) = moving {
+            let threshold = threshold_px * world_units_per_pixel(&cursor);
+            let sprite_snap = snap_bounds_to_sprites(moving, &others, threshold, snap_x, snap_y);
+            for (_, _, transform) in &mut new_transforms {
+                transform.translation += sprite_snap.offset.extend(0.0);
+            }
+            guides.lines = sprite_snap.guides;
+        }
+    }
+
+    if new_transforms.iter().all(|(_, current, transform)| current == transform) {
         return;
-    };
+    }
 
-    match *gizmo_mode {
+    let label = drag_label(*gizmo_mode, new_transforms.len());
+    execute_drag_transforms(&mut commands, label, drag_state.drag_id, new_transforms);
+}
+
+/// Apply one frame of a drag as undoable transform changes (`(entity, current, new)` per target)
+///
+/// Every target gets a command each frame so consecutive frames merge pairwise.
+pub(crate) fn execute_drag_transforms(
+    commands: &mut Commands,
+    label: String,
+    drag_id: u64,
+    new_transforms: Vec<(Entity, Transform, Transform)>,
+) {
+    let mut transform_commands: Vec<SetTransformCommand> = new_transforms
+        .into_iter()
+        .map(|(entity, current, transform)| {
+            SetTransformCommand::new(entity, current, transform).with_merge_id(drag_id)
+        })
+        .collect();
+
+    if transform_commands.len() == 1 {
+        let command = transform_commands.pop().unwrap();
+        commands.execute_undoable(command.with_label(label));
+    } else {
+        let mut group = CompositeCommand::new(label);
+        for command in transform_commands {
+            group.push(Box::new(command));
+        }
+        commands.execute_undoable(group);
+    }
+}
+
+/// Compute how far the mouse has dragged since the drag started
+///
+/// Returns `None` while the amount is undefined (scaling with the cursor on the pivot).
+fn mouse_drag_amount(
+    gizmo_mode: GizmoMode,
+    drag_state: &GizmoDragState,
+    current_world_pos: Vec2,
+    snap: SnapSteps,
+) -> Option<GizmoDragAmount> {
+    let axis = drag_state.drag_axis.unwrap_or(GizmoAxis::XY);
+    // Converts from world space to the gizmo's axes
+    let rotation = drag_state.axes_rotation;
+    let inverse_rotation = rotation.inverse();
+    let center = drag_state.pivot_point;
+
+    match gizmo_mode {
         GizmoMode::Translate => {
             // Move: Calculate delta from drag start
-            let delta = current_world_pos - drag_state.drag_start_world_pos;
-            transform.translation = initial_transform.translation + delta.extend(0.0);
+            let delta = inverse_rotation * (current_world_pos - drag_state.drag_start_world_pos);
+            // World axes snap the gizmo center onto the grid, local axes snap the distance moved
+            let delta = if snap.translate.is_some() {
+                let origin = if rotation == Rot2::IDENTITY { center } else { Vec2::ZERO };
+                let target = origin + delta;
+                Vec2::new(snap_value(target.x, snap.translate), snap_value(target.y, snap.translate)) - origin
+            } else {
+                delta
+            };
+            // Constrain to the picked axis
+            Some(GizmoDragAmount::Translate(match axis {
+                GizmoAxis::X => Vec2::new(delta.x, 0.0),
+                GizmoAxis::Y => Vec2::new(0.0, delta.y),
+                GizmoAxis::XY => delta,
+            }))
         }
         GizmoMode::Rotate => {
-            // Rotate: Calculate angle from entity center
-            let entity_pos = initial_transform.translation.truncate();
+            // Rotate: Calculate angle around the gizmo center
 
-            // Vector from entity to initial mouse position
-            let initial_vec = drag_state.drag_start_world_pos - entity_pos;
-            // Vector from entity to current mouse position
-            let current_vec = current_world_pos - entity_pos;
+            // Vector from gizmo center to initial mouse position
+            let initial_vec = drag_state.drag_start_world_pos - center;
+            // Vector from gizmo center to current mouse position
+            let current_vec = current_world_pos - center;
 
             // Calculate angle difference
             let initial_angle = initial_vec.y.atan2(initial_vec.x);
             let current_angle = current_vec.y.atan2(current_vec.x);
-            let angle_delta = current_angle - initial_angle;
+            Some(GizmoDragAmount::Rotate(snap_value(current_angle - initial_angle, snap.rotate)))
+        }
+        GizmoMode::Scale => {
+            // Scale: Calculate distance ratio from the gizmo center (per axis for edge handles)
+            let initial_vec = inverse_rotation * (drag_state.drag_start_world_pos - center);
+            let current_vec = inverse_rotation * (current_world_pos - center);
 
-            // Apply rotation (rotate around Z axis in 2D)
-            transform.rotation = initial_transform.rotation * Quat::from_rotation_z(angle_delta);
+            let ratio = |initial: f32, current: f32| {
+                // Avoid division by zero
+                (initial.abs() > 0.01).then(|| current.abs() / initial.abs())
+            };
+            let scale_factor = match axis {
+                GizmoAxis::X => ratio(initial_vec.x, current_vec.x).map(|x| Vec2::new(x, 1.0)),
+                GizmoAxis::Y => ratio(initial_vec.y, current_vec.y).map(|y| Vec2::new(1.0, y)),
+                // Apply uniform scale (maintain aspect ratio)
+                GizmoAxis::XY => ratio(initial_vec.length(), current_vec.length()).map(Vec2::splat),
+            };
+
+            // Snap the factor, so a group keeps its relative scales
+            scale_factor.map(|factor| {
+                GizmoDragAmount::Scale(Vec2::new(
+                    snap_value(factor.x, snap.scale),
+                    snap_value(factor.y, snap.scale),
+                ))
+            })
+        }
+    }
+}
+
+/// Parse the amount typed during a drag
+///
+/// Applies to the picked axis; a free move or scale takes "x,y" (a single value moves along X,
+/// or scales uniformly). Rotation is in degrees. Incomplete input such as "-" counts as no change.
+fn typed_drag_amount(gizmo_mode: GizmoMode, axis: GizmoAxis, input: &str) -> GizmoDragAmount {
+    let values: Vec<&str> = input.split(',').collect();
+    let value = |index: usize| values.get(index).and_then(|value| value.trim().parse::<f32>().ok());
+
+    match gizmo_mode {
+        GizmoMode::Translate => {
+            let amount = value(0).unwrap_or(0.0);
+            GizmoDragAmount::Translate(match axis {
+                GizmoAxis::X => Vec2::new(amount, 0.0),
+                GizmoAxis::Y => Vec2::new(0.0, amount),
+                GizmoAxis::XY => Vec2::new(amount, value(1).unwrap_or(0.0)),
+            })
         }
+        GizmoMode::Rotate => GizmoDragAmount::Rotate(value(0).unwrap_or(0.0).to_radians()),
         GizmoMode::Scale => {
-            // Scale: Calculate distance ratio from entity center
-            let entity_pos = initial_transform.translation.truncate();
+            let factor = value(0).unwrap_or(1.0);
+            GizmoDragAmount::Scale(match axis {
+                GizmoAxis::X => Vec2::new(factor, 1.0),
+                GizmoAxis::Y => Vec2::new(1.0, factor),
+                GizmoAxis::XY => Vec2::new(factor, value(1).unwrap_or(factor)),
+            })
+        }
+    }
+}
 
-            let initial_distance = (drag_state.drag_start_world_pos - entity_pos).length();
-            let current_distance = (current_world_pos - entity_pos).length();
+/// Compute an entity's transform for a drag amount, relative to its initial transform
+fn apply_drag_amount(
+    amount: GizmoDragAmount,
+    gizmo_pivot: GizmoPivot,
+    drag_state: &GizmoDragState,
+    initial_transform: Transform,
+    current_transform: Transform,
+) -> Transform {
+    let mut transform = current_transform;
 
-            // Avoid division by zero
-            if initial_distance > 0.01 {
-                let scale_factor = current_distance / initial_distance;
+    // Individual origins keep each entity in place and pivot around its own position
+    let pivot = match gizmo_pivot {
+        GizmoPivot::IndividualOrigins => None,
+        GizmoPivot::Median | GizmoPivot::Primary => Some(drag_state.pivot_point),
+    };
+    let offset = pivot.map(|pivot| initial_transform.translation.truncate() - pivot);
+    // Converts between world space and the gizmo's axes
+    let rotation = drag_state.axes_rotation;
+    let inverse_rotation = rotation.inverse();
 
-                // Apply uniform scale (maintain aspect ratio)
-                let new_scale = initial_transform.scale * scale_factor;
-                // Clamp scale to reasonable values
-                transform.scale = new_scale.clamp(Vec3::splat(0.1), Vec3::splat(10.0));
+    match amount {
+        GizmoDragAmount::Translate(delta) => {
+            transform.translation = initial_transform.translation + (rotation * delta).extend(0.0);
+        }
+        GizmoDragAmount::Rotate(angle_delta) => {
+            // Apply rotation (rotate around Z axis in 2D)
+            transform.rotation = initial_transform.rotation * Quat::from_rotation_z(angle_delta);
+
+            // Orbit around the shared pivot
+            if let (Some(pivot), Some(offset)) = (pivot, offset) {
+                let rotated = Vec2::from_angle(angle_delta).rotate(offset);
+                transform.translation = (pivot + rotated).extend(initial_transform.translation.z);
+            }
+        }
+        // 3D drags are applied by `apply_drag_amount_3d`
+        GizmoDragAmount::Translate3d(_) | GizmoDragAmount::Scale3d(_) => {}
+        GizmoDragAmount::Scale(scale_factor) => {
+            let axes = Quat::from_rotation_z(rotation.as_radians());
+            let new_scale =
+                initial_transform.scale * local_scale_factor(initial_transform.rotation, axes, scale_factor.extend(1.0));
+            // Clamp scale to reasonable values
+            transform.scale = new_scale.clamp(Vec3::splat(MIN_GIZMO_SCALE), Vec3::splat(MAX_GIZMO_SCALE));
+
+            // Spread out from (or gather towards) the shared pivot
+            if let (Some(pivot), Some(offset)) = (pivot, offset) {
+                let scaled_offset = rotation * ((inverse_rotation * offset) * scale_factor);
+                transform.translation = (pivot + scaled_offset).extend(initial_transform.translation.z);
             }
         }
     }
+
+    transform
+}
+
+/// Reset the drag state once a drag is confirmed or cancelled
+fn finish_drag(drag_state: &mut GizmoDragState, ui_focus: &mut UiFocus, guides: &mut SmartGuides) {
+    drag_state.is_dragging = false;
+    drag_state.targets.clear();
+    drag_state.drag_axis = None;
+    drag_state.numeric_input = None;
+    drag_state.amount = None;
+    drag_state.drag_3d = None;
+    ui_focus.text_input_active = false;
+    guides.lines.clear();
 }
 
 /// Handle mouse up to end dragging
 pub fn handle_gizmo_drag_end(
     mouse_button: Res<ButtonInput<MouseButton>>,
     mut drag_state: ResMut<GizmoDragState>,
+    mut ui_focus: ResMut<UiFocus>,
+    mut guides: ResMut<SmartGuides>,
+    mut commands: Commands,
 ) {
-    if mouse_button.just_released(MouseButton::Left) && drag_state.is_dragging {
+    // Once a value is typed, only Enter or Esc end the drag
+    if mouse_button.just_released(MouseButton::Left)
+        && drag_state.is_dragging
+        && drag_state.numeric_input.is_none()
+    {
         info!("Ended drag operation");
-        // TODO: Create undo command here
-        drag_state.is_dragging = false;
-        drag_state.target_entity = None;
-        drag_state.initial_transform = None;
-        drag_state.drag_axis = None;
+        commands.commit_undo_transaction();
+        finish_drag(&mut drag_state, &mut ui_focus, &mut guides);
+    }
+}
+
+/// Numeric entry while dragging: type a value, Enter applies it, Esc cancels the whole drag
+pub fn handle_gizmo_numeric_input(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut key_events: MessageReader<KeyboardInput>,
+    mut drag_state: ResMut<GizmoDragState>,
+    mut ui_focus: ResMut<UiFocus>,
+    mut guides: ResMut<SmartGuides>,
+    mut commands: Commands,
+) {
+    if !drag_state.is_dragging {
+        key_events.clear();
+        return;
+    }
+
+    // Esc restores every entity's initial transform (unless a focused UI field takes it)
+    if keyboard.just_pressed(KeyCode::Escape) && ui_focus.focused_entity.is_none() {
+        info!("Cancelled drag operation");
+        commands.cancel_undo_transaction();
+        finish_drag(&mut drag_state, &mut ui_focus, &mut guides);
+        key_events.clear();
+        return;
+    }
+
+    if drag_state.numeric_input.is_some()
+        && keyboard.any_just_pressed([KeyCode::Enter, KeyCode::NumpadEnter])
+    {
+        info!("Applied typed drag value");
+        commands.commit_undo_transaction();
+        finish_drag(&mut drag_state, &mut ui_focus, &mut guides);
+        key_events.clear();
+        return;
+    }
+
+    if keyboard.just_pressed(KeyCode::Backspace)
+        && let Some(input) = &mut drag_state.numeric_input
+    {
+        input.pop();
+    }
+
+    for event in key_events.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+
+        if let Key::Character(ref text) = event.logical_key {
+            // Only accept numbers, decimal point, minus sign and the X/Y separator
+            for ch in text.chars() {
+                if ch.is_ascii_digit() || matches!(ch, '.' | '-' | ',') {
+                    drag_state.numeric_input.get_or_insert_default().push(ch);
+                }
+            }
+        }
+    }
+
+    // Keep editor shortcuts (W/E/R, ...) from firing while typing
+    let typing = drag_state.numeric_input.is_some();
+    if ui_focus.text_input_active != typing {
+        ui_focus.text_input_active = typing;
+    }
+}
+
+/// Spawn the (hidden) drag readout
+pub fn spawn_gizmo_readout(mut commands: Commands) {
+    commands.spawn((
+        Text::new(""),
+        TextFont {
+            font_size: 12.0,
+            ..default()
+        },
+        TextColor(Color::srgb(0.95, 0.95, 0.95)),
+        Node {
+            position_type: PositionType::Absolute,
+            padding: UiRect::axes(Val::Px(6.0), Val::Px(2.0)),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.7)),
+        GlobalZIndex(100),
+        Visibility::Hidden,
+        Pickable::IGNORE,
+        GizmoReadout,
+        EditorEntity,
+    ));
+}
+
+/// Format a drag amount for the readout
+fn format_drag_amount(amount: GizmoDragAmount, axis: GizmoAxis) -> String {
+    match (amount, axis) {
+        (GizmoDragAmount::Translate(delta), GizmoAxis::X) => format!("X: {:.2}", delta.x),
+        (GizmoDragAmount::Translate(delta), GizmoAxis::Y) => format!("Y: {:.2}", delta.y),
+        (GizmoDragAmount::Translate(delta), GizmoAxis::XY) => format!("X: {:.2}  Y: {:.2}", delta.x, delta.y),
+        (GizmoDragAmount::Rotate(angle), _) => format!("Angle: {:.1}°", angle.to_degrees()),
+        (GizmoDragAmount::Scale(factor), GizmoAxis::X) => format!("Scale X: {:.2}", factor.x),
+        (GizmoDragAmount::Scale(factor), GizmoAxis::Y) => format!("Scale Y: {:.2}", factor.y),
+        (GizmoDragAmount::Scale(factor), GizmoAxis::XY) if factor.x == factor.y => format!("Scale: {:.2}", factor.x),
+        (GizmoDragAmount::Scale(factor), GizmoAxis::XY) => format!("Scale: {:.2}, {:.2}", factor.x, factor.y),
+        (GizmoDragAmount::Translate3d(delta), _) => format!("X: {:.2}  Y: {:.2}  Z: {:.2}", delta.x, delta.y, delta.z),
+        (GizmoDragAmount::Scale3d(factor), _) => format!("Scale: {:.2}, {:.2}, {:.2}", factor.x, factor.y, factor.z),
+    }
+}
+
+/// Show the drag amount (and any typed value) next to the cursor while dragging
+pub fn update_gizmo_readout(
+    drag_state: Res<GizmoDragState>,
+    windows: Query<&Window>,
+    ui_scale: Res<UiScale>,
+    mut readouts: Query<(&mut Text, &mut Node, &mut Visibility), With<GizmoReadout>>,
+) {
+    // UI node positions are in logical pixels divided by the UI scale
+    let cursor_pos = windows
+        .single()
+        .ok()
+        .and_then(|window| window.cursor_position())
+        .map(|cursor_pos| cursor_pos / ui_scale.0);
+
+    for (mut text, mut node, mut visibility) in &mut readouts {
+        let (true, Some(amount), Some(cursor_pos)) = (drag_state.is_dragging, drag_state.amount, cursor_pos) else {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        };
+
+        let axis = drag_state.drag_axis.unwrap_or(GizmoAxis::XY);
+        let mut readout = format_drag_amount(amount, axis);
+        if let Some(input) = &drag_state.numeric_input {
+            readout.push_str(&format!("  [{input}_]"));
+        }
+
+        if text.0 != readout {
+            text.0 = readout;
+        }
+        node.left = Val::Px(cursor_pos.x + 16.0);
+        node.top = Val::Px(cursor_pos.y + 16.0);
+        visibility.set_if_neq(Visibility::Inherited);
+    }
+}
+
+/// Keep an in-progress drag attached to its entities if they get recreated
+pub fn remap_gizmo_drag_target(
+    mut remaps: MessageReader<EntityRemapped>,
+    mut drag_state: ResMut<GizmoDragState>,
+) {
+    for remap in remaps.read() {
+        for target in &mut drag_state.targets {
+            if target.entity == remap.old {
+                target.entity = remap.new;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn world_axis_scale_maps_onto_local_axes() {
+        let factor = Vec3::new(2.0, 1.0, 1.0);
+
+        // Local space: the gizmo axes are the entity's own
+        let rotation = Quat::from_rotation_z(0.3);
+        assert!(local_scale_factor(rotation, rotation, factor).abs_diff_eq(factor, 1e-5));
+
+        // A quarter turn puts the entity's Y axis along world X
+        let rotation = Quat::from_rotation_z(std::f32::consts::FRAC_PI_2);
+        let local = local_scale_factor(rotation, Quat::IDENTITY, factor);
+        assert!(local.abs_diff_eq(Vec3::new(1.0, 2.0, 1.0), 1e-5));
+
+        // Diagonal axes take part of the stretch
+        let local = local_scale_factor(Quat::from_rotation_z(std::f32::consts::FRAC_PI_4), Quat::IDENTITY, factor);
+        assert!(local.x > 1.0 && local.x < 2.0 && (local.x - local.y).abs() < 1e-5);
     }
 }
@@ -0,0 +1,616 @@
+//! Transform gizmos for 3D mode
+//!
+//! Move arrows, rotation rings and scale handles along the X, Y and Z axes, hit tested in
+//! screen space. Drags share `GizmoDragState` with the 2D gizmos, so undo, numeric entry,
+//! Esc and the readout behave the same.
+
+use bevy::camera::primitives::Aabb;
+use bevy::prelude::*;
+use bevy_editor_core::{EditorPreferences, EditorSelection};
+use bevy_editor_undo::UndoCommandsExt;
+use crate::{
+    GizmoDragAmount, GizmoDragState, GizmoDragTarget, GizmoMode, GizmoPivot, GizmoSpace,
+    GridConfig, MAX_GIZMO_SCALE, MIN_GIZMO_SCALE, SnapSteps, ViewportCursor, drag_label, execute_drag_transforms,
+    gizmo_targets, local_scale_factor, snap_value,
+};
+
+/// Handle of the 3D gizmo
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GizmoAxis3d {
+    X,
+    Y,
+    Z,
+    /// Center handle: move in the view plane, or scale uniformly
+    Center,
+}
+
+impl GizmoAxis3d {
+    const AXES: [Self; 3] = [Self::X, Self::Y, Self::Z];
+
+    /// Unit vector of the axis in gizmo space (`None` for the center handle)
+    fn unit(self) -> Option<Vec3> {
+        match self {
+            Self::X => Some(Vec3::X),
+            Self::Y => Some(Vec3::Y),
+            Self::Z => Some(Vec3::Z),
+            Self::Center => None,
+        }
+    }
+
+    fn color(self) -> Color {
+        match self {
+            Self::X => Color::srgb(1.0, 0.0, 0.0),
+            Self::Y => Color::srgb(0.0, 1.0, 0.0),
+            Self::Z => Color::srgb(0.2, 0.4, 1.0),
+            Self::Center => Color::srgb(1.0, 1.0, 1.0),
+        }
+    }
+}
+
+/// Reference points of a drag in 3D mode
+#[derive(Debug, Clone, Copy)]
+pub struct GizmoDrag3d {
+    /// Which handle is being dragged
+    pub axis: GizmoAxis3d,
+    /// Gizmo position when the drag started (the pivot, unless using individual origins)
+    pub pivot_point: Vec3,
+    /// Orientation of the gizmo axes when the drag started
+    pub axes: Quat,
+    /// Where the cursor ray met the handle's axis or plane when the drag started
+    pub start_point: Vec3,
+    /// Screen position of the cursor when the drag started
+    pub start_cursor: Vec2,
+    /// Normal of the view plane when the drag started (free moves stay in it)
+    pub view_normal: Vec3,
+}
+
+/// Gizmo length as a fraction of its distance to the camera, so it keeps a steady screen size
+const GIZMO_SCREEN_SCALE: f32 = 0.15;
+/// Distance (in screen pixels) from a handle that still counts as hitting it
+const HANDLE_HIT_PX: f32 = 8.0;
+/// Color of the handle under the cursor (or being dragged)
+const HIGHLIGHT_COLOR: Color = Color::srgb(1.0, 0.6, 0.1);
+/// Color of the selection bounds
+const SELECTION_COLOR: Color = Color::srgb(1.0, 0.8, 0.0);
+
+/// Where the 3D gizmo is drawn for the given targets (see `gizmo_center`)
+pub fn gizmo_center_3d(
+    pivot: GizmoPivot,
+    primary: Option<Entity>,
+    targets: &[(Entity, Transform)],
+) -> Option<Vec3> {
+    if targets.is_empty() {
+        return None;
+    }
+
+    if pivot == GizmoPivot::Primary
+        && let Some((_, transform)) = targets.iter().find(|(entity, _)| Some(*entity) == primary)
+    {
+        return Some(transform.translation);
+    }
+
+    let sum: Vec3 = targets.iter().map(|(_, transform)| transform.translation).sum();
+    Some(sum / targets.len() as f32)
+}
+
+/// Orientation of the gizmo axes: the primary selection's rotation in Local space, the world
+/// axes otherwise
+fn gizmo_axes_3d(
+    space: GizmoSpace,
+    primary: Option<Entity>,
+    targets: &[(Entity, Transform)],
+) -> Quat {
+    let reference = targets
+        .iter()
+        .find(|(entity, _)| Some(*entity) == primary)
+        .or(targets.first());
+
+    match (space, reference) {
+        (GizmoSpace::Local, Some((_, transform))) => transform.rotation,
+        _ => Quat::IDENTITY,
+    }
+}
+
+/// World length of the gizmo handles at a position
+fn gizmo_size(camera_transform: &GlobalTransform, center: Vec3) -> f32 {
+    camera_transform.translation().distance(center).max(0.01) * GIZMO_SCREEN_SCALE
+}
+
+/// Cursor position and the ray through it, if the cursor is over the viewport
+fn cursor_ray(
+    cursor: &ViewportCursor,
+    camera: &Camera,
+    camera_transform: &GlobalTransform,
+) -> Option<(Vec2, Ray3d)> {
+    let cursor_pos = cursor.position(camera)?;
+    let ray = camera.viewport_to_world(camera_transform, cursor_pos).ok()?;
+    Some((cursor_pos, ray))
+}
+
+/// Point on the line through `origin` along `direction` closest to a ray
+/// (`None` when looking straight down the line)
+fn closest_point_on_line(origin: Vec3, direction: Vec3, ray: Ray3d) -> Option<Vec3> {
+    let ray_direction = *ray.direction;
+    let offset = origin - ray.origin;
+    let a = direction.dot(direction);
+    let b = direction.dot(ray_direction);
+    let c = ray_direction.dot(ray_direction);
+    let denominator = a * c - b * b;
+    if denominator.abs() < 1e-4 {
+        return None;
+    }
+
+    let t = (b * ray_direction.dot(offset) - c * direction.dot(offset)) / denominator;
+    Some(origin + direction * t)
+}
+
+/// Where a ray meets the plane through `origin` with the given normal
+fn plane_hit(origin: Vec3, normal: Vec3, ray: Ray3d) -> Option<Vec3> {
+    let normal = Dir3::new(normal).ok()?;
+    let distance = ray.intersect_plane(origin, InfinitePlane3d::new(normal))?;
+    Some(ray.get_point(distance))
+}
+
+/// Distance from a point to a line segment, in screen space
+fn distance_to_segment(point: Vec2, start: Vec2, end: Vec2) -> f32 {
+    let segment = end - start;
+    let t = ((point - start).dot(segment) / segment.length_squared().max(f32::EPSILON)).clamp(0.0, 1.0);
+    point.distance(start + segment * t)
+}
+
+/// Points along the rotation ring of an axis
+fn ring_points(center: Vec3, normal: Vec3, radius: f32) -> impl Iterator<Item = Vec3> {
+    let rotation = Quat::from_rotation_arc(Vec3::Z, normal);
+    (0..=48).map(move |i| {
+        let angle = i as f32 / 48.0 * std::f32::consts::TAU;
+        center + rotation * Vec3::new(angle.cos(), angle.sin(), 0.0) * radius
+    })
+}
+
+/// Find the gizmo handle under the cursor (closest in screen space)
+pub fn gizmo_hit_test_3d(
+    gizmo_mode: GizmoMode,
+    camera: &Camera,
+    camera_transform: &GlobalTransform,
+    center: Vec3,
+    axes: Quat,
+    cursor_pos: Vec2,
+) -> Option<GizmoAxis3d> {
+    let size = gizmo_size(camera_transform, center);
+    let project = |point: Vec3| camera.world_to_viewport(camera_transform, point).ok();
+    let screen_center = project(center)?;
+
+    // Center handle first, it overlaps the start of every axis
+    if gizmo_mode != GizmoMode::Rotate && cursor_pos.distance(screen_center) <= HANDLE_HIT_PX * 1.5 {
+        return Some(GizmoAxis3d::Center);
+    }
+
+    let mut best: Option<(GizmoAxis3d, f32)> = None;
+    for axis in GizmoAxis3d::AXES {
+        let direction = axes * axis.unit()?;
+        let distance = match gizmo_mode {
+            GizmoMode::Translate | GizmoMode::Scale => {
+                project(center + direction * size).map(|end| distance_to_segment(cursor_pos, screen_center, end))
+            }
+            GizmoMode::Rotate => {
+                let ring: Vec<Vec2> = ring_points(center, direction, size).filter_map(project).collect();
+                ring.windows(2)
+                    .map(|segment| distance_to_segment(cursor_pos, segment[0], segment[1]))
+                    .reduce(f32::min)
+            }
+        };
+
+        if let Some(distance) = distance
+            && distance <= HANDLE_HIT_PX
+            && best.is_none_or(|(_, best)| distance < best)
+        {
+            best = Some((axis, distance));
+        }
+    }
+
+    best.map(|(axis, _)| axis)
+}
+
+/// Draw the 3D gizmo for the currently selected entities (sized for the active viewport)
+pub fn draw_gizmos_3d(
+    cursor: ViewportCursor,
+    selection: Res<EditorSelection>,
+    gizmo_mode: Res<GizmoMode>,
+    gizmo_pivot: Res<GizmoPivot>,
+    gizmo_space: Res<GizmoSpace>,
+    drag_state: Res<GizmoDragState>,
+    transforms: Query<&Transform>,
+    parents: Query<&ChildOf>,
+    mut gizmos: Gizmos,
+) {
+    let Some((_, camera_transform)) = cursor.camera_3d() else {
+        return;
+    };
+
+    let targets = gizmo_targets(&selection, &transforms, &parents);
+    let Some(center) = gizmo_center_3d(*gizmo_pivot, selection.primary(), &targets) else {
+        return;
+    };
+    let axes = gizmo_axes_3d(*gizmo_space, selection.primary(), &targets);
+    let size = gizmo_size(camera_transform, center);
+
+    // Highlight the dragged handle, or the hovered one when not dragging
+    let highlight = match drag_state.drag_3d {
+        Some(drag) => Some(drag.axis),
+        None => drag_state.hovered_axis_3d,
+    };
+    let color = |axis: GizmoAxis3d| if highlight == Some(axis) { HIGHLIGHT_COLOR } else { axis.color() };
+
+    for axis in GizmoAxis3d::AXES {
+        let direction = axes * axis.unit().unwrap_or(Vec3::ZERO);
+        match *gizmo_mode {
+            GizmoMode::Translate => {
+                gizmos.arrow(center, center + direction * size, color(axis));
+            }
+            GizmoMode::Rotate => {
+                gizmos
+                    .circle(Isometry3d::new(center, Quat::from_rotation_arc(Vec3::Z, direction)), size, color(axis))
+                    .resolution(48);
+            }
+            GizmoMode::Scale => {
+                let end = center + direction * size;
+                gizmos.line(center, end, color(axis));
+                gizmos.cube(
+                    Transform::from_translation(end).with_rotation(axes).with_scale(Vec3::splat(size * 0.08)),
+                    color(axis),
+                );
+            }
+        }
+    }
+
+    // Center handle: free move, or uniform scale
+    match *gizmo_mode {
+        GizmoMode::Translate => {
+            gizmos.sphere(Isometry3d::from_translation(center), size * 0.05, color(GizmoAxis3d::Center));
+        }
+        GizmoMode::Scale => {
+            gizmos.cube(
+                Transform::from_translation(center).with_rotation(axes).with_scale(Vec3::splat(size * 0.1)),
+                color(GizmoAxis3d::Center),
+            );
+        }
+        GizmoMode::Rotate => {}
+    }
+
+    // Mark the origins each entity rotates and scales around
+    if *gizmo_pivot == GizmoPivot::IndividualOrigins && targets.len() > 1 {
+        for (_, target) in &targets {
+            gizmos.sphere(Isometry3d::from_translation(target.translation), size * 0.03, Color::WHITE);
+        }
+    }
+}
+
+/// Draw the bounds of the selected entities (a small box for entities without a mesh)
+pub fn draw_selection_bounds_3d(
+    selection: Res<EditorSelection>,
+    query: Query<(&GlobalTransform, Option<&Aabb>)>,
+    mut gizmos: Gizmos,
+) {
+    for entity in selection.selected() {
+        let Ok((transform, aabb)) = query.get(entity) else {
+            continue;
+        };
+
+        let bounds = match aabb {
+            Some(aabb) => Transform::from_translation(aabb.center.into()).with_scale(Vec3::from(aabb.half_extents) * 2.0),
+            None => Transform::from_scale(Vec3::splat(0.25)),
+        };
+        gizmos.cube(transform.mul_transform(bounds), SELECTION_COLOR);
+    }
+}
+
+/// Track which 3D gizmo handle is under the cursor, for highlighting
+pub fn update_gizmo_hover_3d(
+    cursor: ViewportCursor,
+    gizmo_mode: Res<GizmoMode>,
+    gizmo_pivot: Res<GizmoPivot>,
+    gizmo_space: Res<GizmoSpace>,
+    mut drag_state: ResMut<GizmoDragState>,
+    selection: Res<EditorSelection>,
+    transforms: Query<&Transform>,
+    parents: Query<&ChildOf>,
+) {
+    if drag_state.is_dragging {
+        return;
+    }
+
+    let targets = gizmo_targets(&selection, &transforms, &parents);
+    let axes = gizmo_axes_3d(*gizmo_space, selection.primary(), &targets);
+    let hovered = cursor.camera_3d().and_then(|(camera, camera_transform)| {
+        let center = gizmo_center_3d(*gizmo_pivot, selection.primary(), &targets)?;
+        let (cursor_pos, _) = cursor_ray(&cursor, camera, camera_transform)?;
+        gizmo_hit_test_3d(*gizmo_mode, camera, camera_transform, center, axes, cursor_pos)
+    });
+
+    // Avoid change detection churn while the cursor moves over empty space
+    if drag_state.hovered_axis_3d != hovered {
+        drag_state.hovered_axis_3d = hovered;
+    }
+}
+
+/// Where the cursor ray meets the dragged handle: its axis line, or the plane it moves
+/// or rotates in (`None` where that's undefined, e.g. looking straight down an axis)
+fn drag_point(gizmo_mode: GizmoMode, axis: GizmoAxis3d, center: Vec3, axes: Quat, view_normal: Vec3, ray: Ray3d) -> Option<Vec3> {
+    match (gizmo_mode, axis.unit()) {
+        (GizmoMode::Rotate, Some(unit)) => plane_hit(center, axes * unit, ray),
+        (_, Some(unit)) => closest_point_on_line(center, axes * unit, ray),
+        (_, None) => plane_hit(center, view_normal, ray),
+    }
+}
+
+/// Handle mouse down on 3D gizmo handles to start dragging
+pub fn handle_gizmo_drag_start_3d(
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    cursor: ViewportCursor,
+    gizmo_mode: Res<GizmoMode>,
+    gizmo_pivot: Res<GizmoPivot>,
+    gizmo_space: Res<GizmoSpace>,
+    mut drag_state: ResMut<GizmoDragState>,
+    selection: Res<EditorSelection>,
+    transforms: Query<&Transform>,
+    parents: Query<&ChildOf>,
+    mut commands: Commands,
+) {
+    // A drag taking numeric input continues after the mouse is released
+    if !mouse_button.just_pressed(MouseButton::Left) || drag_state.is_dragging {
+        return;
+    }
+
+    let Some((camera, camera_transform)) = cursor.camera_3d() else {
+        return;
+    };
+    let Some((cursor_pos, ray)) = cursor_ray(&cursor, camera, camera_transform) else {
+        return;
+    };
+
+    let targets = gizmo_targets(&selection, &transforms, &parents);
+    let Some(center) = gizmo_center_3d(*gizmo_pivot, selection.primary(), &targets) else {
+        return;
+    };
+    let axes = gizmo_axes_3d(*gizmo_space, selection.primary(), &targets);
+    let Some(axis) = gizmo_hit_test_3d(*gizmo_mode, camera, camera_transform, center, axes, cursor_pos) else {
+        return;
+    };
+
+    let view_normal = *camera_transform.forward();
+    let Some(start_point) = drag_point(*gizmo_mode, axis, center, axes, view_normal, ray) else {
+        return;
+    };
+
+    drag_state.is_dragging = true;
+    drag_state.drag_id += 1;
+    drag_state.targets = targets
+        .iter()
+        .map(|(entity, transform)| GizmoDragTarget {
+            entity: *entity,
+            initial_transform: *transform,
+        })
+        .collect();
+    drag_state.drag_axis = None;
+    drag_state.drag_3d = Some(GizmoDrag3d {
+        axis,
+        pivot_point: center,
+        axes,
+        start_point,
+        start_cursor: cursor_pos,
+        view_normal,
+    });
+    drag_state.numeric_input = None;
+    drag_state.amount = None;
+
+    // The whole drag is one undo entry, which Esc rolls back
+    commands.begin_undo_transaction(drag_label(*gizmo_mode, targets.len()));
+
+    info!("Started {:?} drag of {} entities along {:?}", *gizmo_mode, targets.len(), axis);
+}
+
+/// Handle mouse drag (or typed values) to update the transforms of the dragged entities in 3D
+pub fn handle_gizmo_drag_3d(
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    cursor: ViewportCursor,
+    gizmo_mode: Res<GizmoMode>,
+    gizmo_pivot: Res<GizmoPivot>,
+    mut drag_state: ResMut<GizmoDragState>,
+    transforms: Query<&Transform>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    preferences: Res<EditorPreferences>,
+    grid: Res<GridConfig>,
+    mut commands: Commands,
+) {
+    let typing = drag_state.numeric_input.is_some();
+    if !drag_state.is_dragging || (!mouse_button.pressed(MouseButton::Left) && !typing) {
+        return;
+    }
+
+    let Some(drag) = drag_state.drag_3d else {
+        return;
+    };
+
+    // Typed values are exact, the mouse snaps
+    let amount = if let Some(input) = &drag_state.numeric_input {
+        Some(typed_drag_amount_3d(*gizmo_mode, drag, input))
+    } else {
+        let Some((camera, camera_transform)) = cursor.camera_3d() else {
+            return;
+        };
+        let Some((cursor_pos, ray)) = cursor_ray(&cursor, camera, camera_transform) else {
+            return;
+        };
+
+        // 3D translation snaps to the 3D grid unless a custom step is set
+        let grid = cursor.grid(&grid);
+        let mut snap = SnapSteps::active(&preferences.snap, grid, &keyboard);
+        if snap.translate.is_some() {
+            snap.translate = Some(preferences.snap.translate_step.unwrap_or(grid.cell_size_3d));
+        }
+
+        mouse_drag_amount_3d(*gizmo_mode, drag, camera, camera_transform, cursor_pos, ray, snap)
+    };
+    drag_state.amount = amount;
+    let Some(amount) = amount else {
+        return;
+    };
+
+    let new_transforms: Vec<(Entity, Transform, Transform)> = drag_state
+        .targets
+        .iter()
+        .filter_map(|target| {
+            let current_transform = *transforms.get(target.entity).ok()?;
+            let transform = apply_drag_amount_3d(amount, *gizmo_pivot, drag, target.initial_transform, current_transform);
+            Some((target.entity, current_transform, transform))
+        })
+        .collect();
+
+    if new_transforms.iter().all(|(_, current, transform)| current == transform) {
+        return;
+    }
+
+    let label = drag_label(*gizmo_mode, new_transforms.len());
+    execute_drag_transforms(&mut commands, label, drag_state.drag_id, new_transforms);
+}
+
+/// Compute how far the mouse has dragged a 3D handle since the drag started
+///
+/// Returns `None` while the amount is undefined (e.g. looking straight down the dragged axis).
+fn mouse_drag_amount_3d(
+    gizmo_mode: GizmoMode,
+    drag: GizmoDrag3d,
+    camera: &Camera,
+    camera_transform: &GlobalTransform,
+    cursor_pos: Vec2,
+    ray: Ray3d,
+    snap: SnapSteps,
+) -> Option<GizmoDragAmount> {
+    let center = drag.pivot_point;
+    let direction = drag.axis.unit().map(|unit| drag.axes * unit);
+
+    match (gizmo_mode, direction) {
+        (GizmoMode::Translate, Some(direction)) => {
+            let point = drag_point(gizmo_mode, drag.axis, center, drag.axes, drag.view_normal, ray)?;
+            let distance = (point - drag.start_point).dot(direction);
+            Some(GizmoDragAmount::Translate3d(direction * snap_value(distance, snap.translate)))
+        }
+        (GizmoMode::Translate, None) => {
+            // Free move in the view plane, snapping the gizmo center onto the grid
+            let point = drag_point(gizmo_mode, drag.axis, center, drag.axes, drag.view_normal, ray)?;
+            let target = center + point - drag.start_point;
+            let snapped = Vec3::new(
+                snap_value(target.x, snap.translate),
+                snap_value(target.y, snap.translate),
+                snap_value(target.z, snap.translate),
+            );
+            Some(GizmoDragAmount::Translate3d(snapped - center))
+        }
+        (GizmoMode::Rotate, Some(direction)) => {
+            let point = drag_point(gizmo_mode, drag.axis, center, drag.axes, drag.view_normal, ray)?;
+            let initial = drag.start_point - center;
+            let current = point - center;
+            let angle = direction.dot(initial.cross(current)).atan2(initial.dot(current));
+            Some(GizmoDragAmount::Rotate(snap_value(angle, snap.rotate)))
+        }
+        (GizmoMode::Rotate, None) => None,
+        (GizmoMode::Scale, Some(direction)) => {
+            let point = drag_point(gizmo_mode, drag.axis, center, drag.axes, drag.view_normal, ray)?;
+            let initial = (drag.start_point - center).dot(direction);
+            // Avoid division by zero
+            if initial.abs() < 1e-4 {
+                return None;
+            }
+            let factor = snap_value((point - center).dot(direction) / initial, snap.scale);
+            let unit = drag.axis.unit()?;
+            Some(GizmoDragAmount::Scale3d(Vec3::ONE + unit * (factor - 1.0)))
+        }
+        (GizmoMode::Scale, None) => {
+            // Uniform scale by how far the cursor moved from the gizmo center on screen
+            let screen_center = camera.world_to_viewport(camera_transform, center).ok()?;
+            let initial = drag.start_cursor.distance(screen_center);
+            if initial < 1.0 {
+                return None;
+            }
+            let factor = snap_value(cursor_pos.distance(screen_center) / initial, snap.scale);
+            Some(GizmoDragAmount::Scale3d(Vec3::splat(factor)))
+        }
+    }
+}
+
+/// Parse the amount typed during a 3D drag
+///
+/// Applies along the dragged axis; the center handle takes "x,y,z" (a single value scales
+/// uniformly). Rotation is in degrees. Incomplete input such as "-" counts as no change.
+fn typed_drag_amount_3d(gizmo_mode: GizmoMode, drag: GizmoDrag3d, input: &str) -> GizmoDragAmount {
+    let values: Vec<&str> = input.split(',').collect();
+    let value = |index: usize| values.get(index).and_then(|value| value.trim().parse::<f32>().ok());
+
+    match (gizmo_mode, drag.axis.unit()) {
+        (GizmoMode::Translate, Some(unit)) => {
+            GizmoDragAmount::Translate3d(drag.axes * unit * value(0).unwrap_or(0.0))
+        }
+        (GizmoMode::Translate, None) => GizmoDragAmount::Translate3d(Vec3::new(
+            value(0).unwrap_or(0.0),
+            value(1).unwrap_or(0.0),
+            value(2).unwrap_or(0.0),
+        )),
+        (GizmoMode::Rotate, _) => GizmoDragAmount::Rotate(value(0).unwrap_or(0.0).to_radians()),
+        (GizmoMode::Scale, Some(unit)) => {
+            GizmoDragAmount::Scale3d(Vec3::ONE + unit * (value(0).unwrap_or(1.0) - 1.0))
+        }
+        (GizmoMode::Scale, None) => {
+            let factor = value(0).unwrap_or(1.0);
+            GizmoDragAmount::Scale3d(Vec3::new(factor, value(1).unwrap_or(factor), value(2).unwrap_or(factor)))
+        }
+    }
+}
+
+/// Compute an entity's transform for a 3D drag amount, relative to its initial transform
+fn apply_drag_amount_3d(
+    amount: GizmoDragAmount,
+    gizmo_pivot: GizmoPivot,
+    drag: GizmoDrag3d,
+    initial_transform: Transform,
+    current_transform: Transform,
+) -> Transform {
+    let mut transform = current_transform;
+
+    // Individual origins keep each entity in place and pivot around its own position
+    let pivot = match gizmo_pivot {
+        GizmoPivot::IndividualOrigins => None,
+        GizmoPivot::Median | GizmoPivot::Primary => Some(drag.pivot_point),
+    };
+
+    match amount {
+        GizmoDragAmount::Translate3d(delta) => {
+            transform.translation = initial_transform.translation + delta;
+        }
+        GizmoDragAmount::Rotate(angle) => {
+            let axis = drag.axes * drag.axis.unit().unwrap_or(Vec3::Z);
+            let rotation = Quat::from_axis_angle(axis, angle);
+            transform.rotation = rotation * initial_transform.rotation;
+
+            // Orbit around the shared pivot
+            if let Some(pivot) = pivot {
+                transform.translation = pivot + rotation * (initial_transform.translation - pivot);
+            }
+        }
+        GizmoDragAmount::Scale3d(scale_factor) => {
+            let new_scale =
+                initial_transform.scale * local_scale_factor(initial_transform.rotation, drag.axes, scale_factor);
+            // Clamp scale to reasonable values
+            transform.scale = new_scale.clamp(Vec3::splat(MIN_GIZMO_SCALE), Vec3::splat(MAX_GIZMO_SCALE));
+
+            // Spread out from (or gather towards) the shared pivot, along the gizmo axes
+            if let Some(pivot) = pivot {
+                let offset = drag.axes.inverse() * (initial_transform.translation - pivot);
+                transform.translation = pivot + drag.axes * (offset * scale_factor);
+            }
+        }
+        // 2D drags are applied by `apply_drag_amount`
+        GizmoDragAmount::Translate(_) | GizmoDragAmount::Scale(_) => {}
+    }
+
+    transform
+}
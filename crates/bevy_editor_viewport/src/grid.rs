@@ -1,14 +1,25 @@
 //! Infinite grid rendering using bevy_gizmos
+//!
+//! Each viewport draws its grid into its own retained gizmo, using the viewport's
+//! `GridConfig` component if it has one and the `GridConfig` resource otherwise. The gizmo
+//! is only rebuilt when the mode, the config or (in 2D) the viewport's camera changes.
 
+use bevy::ecs::entity::EntityHashSet;
 use bevy::prelude::*;
+use crate::{ViewportCameras, ViewportMode};
 
-/// Grid configuration for 2D XY plane
-#[derive(Debug, Resource)]
+/// Grid configuration for the 2D XY plane and the 3D ground (XZ) plane
+///
+/// As a resource it's the default for every viewport; as a component on an `EditorViewport`
+/// it overrides the grid (and grid snapping) in that viewport.
+#[derive(Debug, Clone, Resource, Component)]
 pub struct GridConfig {
     pub enabled: bool,
-    /// Size of each grid cell
+    /// Size of each grid cell at zoom level 1 (the 2D grid subdivides or merges cells as you zoom)
     pub cell_size: f32,
-    /// Number of cells to draw in each direction
+    /// Size of each grid cell in 3D mode (world units are meters rather than pixels)
+    pub cell_size_3d: f32,
+    /// Number of cells to draw across the 3D grid (the 2D grid covers the visible area)
     pub cell_count: i32,
     /// How many cells between major lines
     pub major_line_interval: i32,
@@ -17,6 +28,7 @@ pub struct GridConfig {
     pub color_major: Color,
     pub color_axis_x: Color,
     pub color_axis_y: Color,
+    pub color_axis_z: Color,
 }
 
 impl Default for GridConfig {
@@ -24,59 +36,217 @@ impl Default for GridConfig {
         Self {
             enabled: true,
             cell_size: 50.0,  // Larger cells for 2D pixel-based coordinates
+            cell_size_3d: 1.0,
             cell_count: 40,
             major_line_interval: 5,
             color_minor: Color::srgba(0.2, 0.2, 0.2, 0.5),
             color_major: Color::srgba(0.4, 0.4, 0.4, 0.8),
             color_axis_x: Color::srgba(1.0, 0.3, 0.3, 0.8),
             color_axis_y: Color::srgba(0.3, 1.0, 0.3, 0.8), // Green for Y axis
+            color_axis_z: Color::srgba(0.3, 0.5, 1.0, 0.8), // Blue for Z axis
         }
     }
 }
 
-/// System to draw the 2D grid in XY plane using gizmos
-pub fn draw_grid(
-    mut gizmos: Gizmos,
-    config: Res<GridConfig>,
+/// Smallest on-screen spacing (in pixels) of the finest grid lines drawn
+const MIN_LINE_SPACING_PX: f32 = 12.0;
+
+/// Grid spacing for a zoom level: the base cell size multiplied or divided by the major
+/// line interval until minor lines are at least `MIN_LINE_SPACING_PX` apart on screen
+///
+/// Returns the spacing and how far the minor lines have faded in (0 = about to be dropped),
+/// or `None` when the cell size or zoom level can't produce a usable spacing.
+pub fn adaptive_grid_step(cell_size: f32, major_line_interval: i32, scale: f32) -> Option<(f32, f32)> {
+    if !(cell_size.is_finite() && cell_size > 0.0 && scale.is_finite() && scale > 0.0) {
+        return None;
+    }
+
+    let interval = major_line_interval.max(2) as f32;
+    let min_step = MIN_LINE_SPACING_PX * scale;
+    let level = (min_step / cell_size).log(interval).ceil();
+    let step = cell_size * interval.powf(level);
+    if !(step.is_finite() && step > 0.0) {
+        return None;
+    }
+
+    // 0 right where this level starts (minor lines at the minimum spacing), 1 right before
+    // the next finer level would take over
+    let fade = ((step / min_step - 1.0) / (interval - 1.0)).clamp(0.0, 1.0);
+    Some((step, fade))
+}
+
+/// System to redraw the grid of every viewport whose grid is out of date for the current mode
+pub fn draw_grids(
+    default_config: Res<GridConfig>,
+    mode: Res<ViewportMode>,
+    viewports: Query<(Entity, Ref<ViewportCameras>, Option<Ref<GridConfig>>)>,
+    mut removed_overrides: RemovedComponents<GridConfig>,
+    cameras: Query<(Ref<GlobalTransform>, Ref<Projection>)>,
+    mut gizmo_assets: ResMut<Assets<GizmoAsset>>,
+) {
+    // Viewports that went back to the default grid
+    let removed_overrides: EntityHashSet = removed_overrides.read().collect();
+
+    for (entity, viewport, config) in &viewports {
+        // The 3D grid is fixed in the world, the 2D one covers what the camera sees
+        let camera_moved = *mode == ViewportMode::TwoD
+            && cameras
+                .get(viewport.camera_2d)
+                .is_ok_and(|(transform, projection)| transform.is_changed() || projection.is_changed());
+        let config_changed = match &config {
+            Some(config) => config.is_changed(),
+            None => default_config.is_changed() || removed_overrides.contains(&entity),
+        };
+        if !(viewport.is_added() || mode.is_changed() || config_changed || camera_moved) {
+            continue;
+        }
+
+        let Some(gizmo) = gizmo_assets.get_mut(&viewport.grid_gizmo) else {
+            continue;
+        };
+        gizmo.clear();
+
+        let config = config.as_deref().unwrap_or(&default_config);
+        if !config.enabled {
+            continue;
+        }
+
+        match *mode {
+            ViewportMode::TwoD => {
+                if let Ok((camera_transform, projection)) = cameras.get(viewport.camera_2d)
+                    && let Projection::Orthographic(ortho) = &*projection
+                {
+                    draw_grid(gizmo, config, &camera_transform, ortho);
+                }
+            }
+            ViewportMode::ThreeD => draw_grid_3d(gizmo, config),
+        }
+    }
+}
+
+/// Draw the 2D grid in the XY plane, covering the visible camera area
+///
+/// The spacing adapts to the zoom level, minor lines fading out as you zoom out until the
+/// major lines take their place.
+fn draw_grid(
+    gizmos: &mut GizmoAsset,
+    config: &GridConfig,
+    camera_transform: &GlobalTransform,
+    ortho: &OrthographicProjection,
 ) {
-    if !config.enabled {
+    // Visible world rectangle (the 2D camera doesn't rotate)
+    let offset = camera_transform.translation().truncate();
+    let visible = Rect::from_corners(ortho.area.min + offset, ortho.area.max + offset);
+    if !(visible.min.is_finite() && visible.max.is_finite()) {
         return;
     }
 
-    let half_count = config.cell_count / 2;
-    let extent = half_count as f32 * config.cell_size;
+    // A degenerate zoom or cell size would turn the line loops below into the full i64 range
+    let Some((step, fade)) = adaptive_grid_step(config.cell_size, config.major_line_interval, ortho.scale) else {
+        return;
+    };
+    let interval = config.major_line_interval.max(2) as i64;
 
-    // Draw horizontal grid lines (along X axis, varying Y)
-    for i in -half_count..=half_count {
-        let offset = i as f32 * config.cell_size;
-        let start = Vec2::new(-extent, offset);
-        let end = Vec2::new(extent, offset);
-
-        let color = if i == 0 {
-            config.color_axis_x // X axis (horizontal center line)
-        } else if i % config.major_line_interval == 0 {
-            config.color_major
+    // Minor lines fade out, major lines fade towards minor so they blend into the next level
+    let minor_color = config.color_minor.with_alpha(config.color_minor.alpha() * fade);
+    let major_color = config.color_minor.mix(&config.color_major, fade);
+
+    let line_color = |index: i64, axis_color: Color| {
+        if index == 0 {
+            axis_color
+        } else if index % interval == 0 {
+            major_color
         } else {
-            config.color_minor
-        };
+            minor_color
+        }
+    };
 
-        gizmos.line_2d(start, end, color);
+    // Draw horizontal grid lines (along X axis, varying Y)
+    let (first, last) = ((visible.min.y / step).floor() as i64, (visible.max.y / step).ceil() as i64);
+    for i in first..=last {
+        let y = i as f32 * step;
+        gizmos.line_2d(
+            Vec2::new(visible.min.x, y),
+            Vec2::new(visible.max.x, y),
+            line_color(i, config.color_axis_x), // X axis (horizontal center line)
+        );
     }
 
     // Draw vertical grid lines (along Y axis, varying X)
+    let (first, last) = ((visible.min.x / step).floor() as i64, (visible.max.x / step).ceil() as i64);
+    for i in first..=last {
+        let x = i as f32 * step;
+        gizmos.line_2d(
+            Vec2::new(x, visible.min.y),
+            Vec2::new(x, visible.max.y),
+            line_color(i, config.color_axis_y), // Y axis (vertical center line)
+        );
+    }
+}
+
+/// Draw the 3D ground grid in the XZ plane
+fn draw_grid_3d(gizmos: &mut GizmoAsset, config: &GridConfig) {
+    let half_count = config.cell_count / 2;
+    let interval = config.major_line_interval.max(2);
+    let extent = half_count as f32 * config.cell_size_3d;
+
+    // Lines along X (varying Z), then lines along Z (varying X)
     for i in -half_count..=half_count {
-        let offset = i as f32 * config.cell_size;
-        let start = Vec2::new(offset, -extent);
-        let end = Vec2::new(offset, extent);
-
-        let color = if i == 0 {
-            config.color_axis_y // Y axis (vertical center line)
-        } else if i % config.major_line_interval == 0 {
-            config.color_major
-        } else {
-            config.color_minor
+        let offset = i as f32 * config.cell_size_3d;
+
+        let line_color = |axis_color: Color| {
+            if i == 0 {
+                axis_color
+            } else if i % interval == 0 {
+                config.color_major
+            } else {
+                config.color_minor
+            }
         };
 
-        gizmos.line_2d(start, end, color);
+        gizmos.line(
+            Vec3::new(-extent, 0.0, offset),
+            Vec3::new(extent, 0.0, offset),
+            line_color(config.color_axis_x), // X axis (at Z = 0)
+        );
+        gizmos.line(
+            Vec3::new(offset, 0.0, -extent),
+            Vec3::new(offset, 0.0, extent),
+            line_color(config.color_axis_z), // Z axis (at X = 0)
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grid_step_keeps_minimum_spacing() {
+        for scale in [0.01, 0.1, 1.0, 3.7, 100.0] {
+            let (step, fade) = adaptive_grid_step(50.0, 5, scale).unwrap();
+            assert!(step / scale >= MIN_LINE_SPACING_PX - 1e-3, "scale {scale}: step {step}");
+            // One level finer would be below the minimum spacing
+            assert!(step / 5.0 / scale < MIN_LINE_SPACING_PX, "scale {scale}: step {step}");
+            assert!((0.0..=1.0).contains(&fade));
+        }
+    }
+
+    #[test]
+    fn grid_step_fades_in_new_level() {
+        // Cells just above the minimum spacing: the base level, minor lines nearly faded out
+        let (step, fade) = adaptive_grid_step(50.0, 5, 48.0 / MIN_LINE_SPACING_PX).unwrap();
+        assert!((step - 50.0).abs() < 1e-3);
+        assert!(fade < 0.05);
+    }
+
+    #[test]
+    fn grid_step_rejects_degenerate_input() {
+        assert!(adaptive_grid_step(50.0, 5, 0.0).is_none());
+        assert!(adaptive_grid_step(50.0, 5, f32::NAN).is_none());
+        assert!(adaptive_grid_step(50.0, 5, f32::INFINITY).is_none());
+        assert!(adaptive_grid_step(0.0, 5, 1.0).is_none());
+        // Intervals below 2 fall back to 2 instead of dividing by zero
+        assert!(adaptive_grid_step(50.0, 0, 1.0).is_some());
     }
 }
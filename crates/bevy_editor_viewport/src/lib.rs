@@ -1,4 +1,8 @@
 //! 3D/2D viewport with editor camera and gizmos
+//!
+//! `ViewportMode` switches between the 2D (sprite) and 3D (mesh) cameras, grids and gizmos.
+//! Each `EditorViewport` node renders offscreen through its own cameras; input goes to the
+//! hovered one. A `GameViewport` node previews the scene's own camera.
 
 use bevy::prelude::*;
 use bevy::picking::prelude::*;
@@ -6,13 +10,19 @@ use bevy_editor_core::EditorSelection;
 
 pub mod camera;
 pub mod gizmos;
+pub mod gizmos_3d;
 pub mod grid;
 pub mod picking;
+pub mod render_target;
+pub mod snapping;
 
 pub use camera::*;
 pub use gizmos::*;
+pub use gizmos_3d::*;
 pub use grid::*;
 pub use picking::*;
+pub use render_target::*;
+pub use snapping::*;
 
 /// Plugin for viewport functionality
 pub struct EditorViewportPlugin;
@@ -26,77 +36,51 @@ impl Plugin for EditorViewportPlugin {
             .init_resource::<GridConfig>()
             // Initialize gizmo resources
             .init_resource::<GizmoMode>()
+            .init_resource::<GizmoPivot>()
+            .init_resource::<GizmoSpace>()
+            .init_resource::<SmartGuides>()
+            .init_resource::<MarqueeSelection>()
+            // Click-to-select for every entity, however it was created
+            .add_observer(on_viewport_click)
+            .add_observer(despawn_viewport_cameras)
+            .add_observer(despawn_game_preview_camera)
             .init_resource::<GizmoDragState>()
             // Add systems
             .add_systems(Update, (
-                draw_grid,
-                draw_selection_outline,
-                // Gizmo systems
-                draw_gizmos,
+                draw_grids,
+                // 2D mode
+                (
+                    draw_selection_outline,
+                    draw_gizmos,
+                    update_gizmo_hover,
+                    draw_smart_guides,
+                    draw_marquee,
+                ).run_if(ViewportMode::is_2d),
+                // 3D mode
+                (
+                    draw_selection_bounds_3d,
+                    draw_gizmos_3d,
+                    update_gizmo_hover_3d,
+                ).run_if(ViewportMode::is_3d),
                 handle_gizmo_mode_shortcuts,
-                handle_gizmo_drag_start,
-                handle_gizmo_drag,
-                handle_gizmo_drag_end,
+                (
+                    handle_gizmo_numeric_input,
+                    handle_gizmo_drag_start.run_if(ViewportMode::is_2d),
+                    handle_gizmo_drag_start_3d.run_if(ViewportMode::is_3d),
+                    handle_gizmo_drag.run_if(ViewportMode::is_2d),
+                    handle_gizmo_drag_3d.run_if(ViewportMode::is_3d),
+                    handle_gizmo_drag_end,
+                    update_gizmo_readout,
+                    // After drag start, so grabbing a handle doesn't also start a box
+                    handle_marquee_selection.run_if(ViewportMode::is_2d),
+                ).chain(),
+                remap_gizmo_drag_target,
+                (setup_game_viewports, sync_game_preview_cameras).chain(),
             ))
-            // Add test scene for now
-            .add_systems(Startup, spawn_test_scene);
+            .add_systems(Startup, (spawn_gizmo_readout, use_overlay_gizmo_layer));
     }
 }
 
-/// Spawn many test sprites for 2D scene editing (testing picking and scrolling)
-fn spawn_test_scene(
-    mut commands: Commands,
-) {
-    // Spawn a grid of sprites to test both picking and panel scrolling
-    // Grid: 6 rows x 5 columns = 30 sprites
-    let colors = [
-        (Color::srgb(0.2, 0.5, 1.0), "Blue"),      // Blue
-        (Color::srgb(1.0, 0.2, 0.2), "Red"),       // Red
-        (Color::srgb(1.0, 0.9, 0.2), "Yellow"),    // Yellow
-        (Color::srgb(0.2, 1.0, 0.2), "Green"),     // Green
-        (Color::srgb(1.0, 0.5, 0.2), "Orange"),    // Orange
-        (Color::srgb(0.8, 0.2, 1.0), "Purple"),    // Purple
-    ];
-
-    let spacing = 100.0;
-    let start_x = -200.0;
-    let start_y = 200.0;
-
-    for row in 0..6 {
-        for col in 0..5 {
-            let x = start_x + (col as f32 * spacing);
-            let y = start_y - (row as f32 * spacing);
-            let (color, color_name) = colors[row % colors.len()];
-
-            let size = 50.0 + (row * 5) as f32; // Vary size slightly
-
-            commands.spawn((
-                Sprite {
-                    color,
-                    custom_size: Some(Vec2::new(size, size)),
-                    ..default()
-                },
-                Transform::from_xyz(x, y, 0.0),
-                Name::new(format!("{}_{}_{}", color_name, row, col)),
-                Pickable {
-                    should_block_lower: false,
-                    is_hoverable: true,
-                },
-            ))
-            .observe(on_entity_click);
-        }
-    }
-}
-
-/// Handle entity click events to update selection
-fn on_entity_click(
-    trigger: On<Pointer<Click>>,
-    mut selection: ResMut<EditorSelection>,
-) {
-    // Update selection (for now, just single selection - no multi-select yet)
-    selection.select(trigger.entity);
-}
-
 /// Draw selection outline using gizmos (2D rectangles for sprites)
 fn draw_selection_outline(
     mut gizmos: Gizmos,
@@ -109,19 +93,8 @@ fn draw_selection_outline(
     for entity in selection.selected() {
         // Get the transform and sprite of the selected entity
         if let Ok((transform, sprite)) = query.get(entity) {
-            // Get sprite size
-            let size = if let Some(sprite) = sprite {
-                // Use sprite's custom_size if available
-                if let Some(custom_size) = sprite.custom_size {
-                    Vec2::new(custom_size.x, custom_size.y)
-                } else {
-                    // Default size if no custom_size
-                    Vec2::new(64.0, 64.0)
-                }
-            } else {
-                // Fallback size for entities without sprites
-                Vec2::new(64.0, 64.0)
-            };
+            // Get sprite size (custom size, or a fallback for entities without one)
+            let size = sprite_size(sprite);
 
             // Scale by transform and add offset to make rectangle slightly larger
             let scaled_size = size * transform.scale.truncate() * 1.1;
@@ -1,6 +1,20 @@
 //! Mouse picking for entity selection
+//!
+//! A global click observer selects any non-editor entity (spawned or loaded): Ctrl toggles,
+//! Shift adds, clicking again in the same spot cycles through overlapping entities, and
+//! clicking empty space clears the selection.
+//!
+//! Includes box (marquee) selection: left-drag on empty viewport space selects every sprite
+//! whose bounds intersect the box. Shift adds to the selection, Ctrl subtracts from it.
 
 use bevy::prelude::*;
+use bevy::picking::hover::HoverMap;
+use bevy::picking::pointer::{PointerButton, PointerId};
+use bevy_editor_core::{EditorEntity, EditorSelection};
+use crate::{
+    EditorViewport, GizmoDragState, ViewportCameras, ViewportCursor, ViewportMode, cursor_world_position,
+    over_empty_viewport, sprite_bounds, sprite_size,
+};
 
 /// Result of a picking operation
 #[derive(Debug, Clone)]
@@ -12,3 +26,265 @@ pub struct PickingResult {
 }
 
 // Note: Pickable component removed - use bevy::picking::Pickable instead
+
+/// How close (in screen pixels) a click has to be to the previous one to cycle overlapping entities
+const CYCLE_CLICK_DISTANCE: f32 = 3.0;
+
+/// Whether a world position lies inside a sprite (accounting for its rotation and scale)
+pub fn sprite_contains(transform: &Transform, sprite: Option<&Sprite>, point: Vec2) -> bool {
+    let rotation = Rot2::radians(transform.rotation.to_euler(bevy::math::EulerRot::XYZ).2);
+    let local = rotation.inverse() * (point - transform.translation.truncate());
+    let half_size = sprite_size(sprite) * transform.scale.truncate().abs() / 2.0;
+    local.x.abs() <= half_size.x && local.y.abs() <= half_size.y
+}
+
+/// Everything under a click, topmost first: the picked entity, then every other
+/// sprite containing the point (picking only reports the topmost blocking hit)
+pub fn overlapping_picks(
+    top: PickingResult,
+    world_pos: Vec2,
+    camera_z: f32,
+    sprites: &Query<(Entity, &Transform, &Sprite), Without<EditorEntity>>,
+) -> Vec<PickingResult> {
+    let mut others: Vec<PickingResult> = sprites
+        .iter()
+        .filter(|(entity, transform, sprite)| {
+            *entity != top.entity && sprite_contains(transform, Some(sprite), world_pos)
+        })
+        .map(|(entity, transform, _)| PickingResult {
+            entity,
+            position: world_pos.extend(transform.translation.z),
+            normal: Vec3::Z,
+            distance: camera_z - transform.translation.z,
+        })
+        .collect();
+    // Stable order for overlapping entities at the same depth, so cycling visits each one
+    others.sort_by(|a, b| a.distance.total_cmp(&b.distance).then(a.entity.cmp(&b.entity)));
+
+    let mut picks = vec![top];
+    picks.extend(others);
+    picks
+}
+
+/// Overlapping entities under the previous click, for cycling
+#[derive(Default)]
+pub struct PickCycle {
+    /// Screen position of the previous click
+    position: Vec2,
+    /// Entities that were under it, topmost first
+    entities: Vec<Entity>,
+    /// Which of them was picked
+    index: usize,
+}
+
+/// Select entities clicked in the viewport
+pub fn on_viewport_click(
+    click: On<Pointer<Click>>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    viewport_mode: Res<ViewportMode>,
+    drag_state: Res<GizmoDragState>,
+    marquee: Res<MarqueeSelection>,
+    mut selection: ResMut<EditorSelection>,
+    hover_map: Res<HoverMap>,
+    viewports: Query<&PointerId, With<EditorViewport>>,
+    viewport_cameras: Query<(&PointerId, &ViewportCameras)>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    editor_entities: Query<(), With<EditorEntity>>,
+    parents: Query<&ChildOf>,
+    sprites: Query<(Entity, &Transform, &Sprite), Without<EditorEntity>>,
+    mut cycle: Local<PickCycle>,
+) {
+    // Clicks bubble up the hierarchy, only handle them where they started
+    if click.button != PointerButton::Primary || click.entity != click.original_event_target() {
+        return;
+    }
+
+    // Releasing a gizmo drag or a box selection isn't a click
+    if drag_state.is_dragging || marquee.active {
+        return;
+    }
+
+    let shift = keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    let ctrl = keyboard.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+
+    // The viewport itself was hit: a click on empty space, unless the viewport's own pointer
+    // is over an entity (that pointer's click selects it)
+    if let Ok(viewport_pointer) = viewports.get(click.entity) {
+        let over_entity = hover_map
+            .get(viewport_pointer)
+            .is_some_and(|hits| !hits.is_empty());
+        if !over_entity {
+            if !shift && !ctrl {
+                selection.clear();
+            }
+            *cycle = PickCycle::default();
+        }
+        return;
+    }
+
+    // Editor UI and gizmo helpers are never selectable
+    let is_editor_entity = |entity: Entity| {
+        editor_entities.contains(entity)
+            || parents.iter_ancestors(entity).any(|ancestor| editor_entities.contains(ancestor))
+    };
+    if is_editor_entity(click.entity) {
+        return;
+    }
+
+    let screen_pos = click.pointer_location.position;
+
+    // The camera of the viewport the click came through
+    let camera = viewport_cameras
+        .iter()
+        .find(|(pointer, _)| **pointer == click.pointer_id)
+        .and_then(|(_, viewport)| cameras.get(viewport.camera_2d).ok());
+
+    // Overlapping sprites are only found in 2D, in 3D the mesh hit is the pick
+    let entities: Vec<Entity> = match (*viewport_mode, camera) {
+        (ViewportMode::TwoD, Some((camera, camera_transform))) => {
+            let world_pos = camera
+                .viewport_to_world_2d(camera_transform, screen_pos)
+                .unwrap_or_else(|_| click.hit.position.unwrap_or_default().truncate());
+
+            let top = PickingResult {
+                entity: click.entity,
+                position: click.hit.position.unwrap_or(world_pos.extend(0.0)),
+                normal: click.hit.normal.unwrap_or(Vec3::Z),
+                distance: click.hit.depth,
+            };
+            overlapping_picks(top, world_pos, camera_transform.translation().z, &sprites)
+                .into_iter()
+                .map(|pick| pick.entity)
+                .collect()
+        }
+        _ => vec![click.entity],
+    };
+
+    // Clicking the same stack again picks the next entity down
+    let index = if screen_pos.distance(cycle.position) <= CYCLE_CLICK_DISTANCE && cycle.entities == entities {
+        (cycle.index + 1) % entities.len()
+    } else {
+        0
+    };
+    let picked = entities[index];
+    *cycle = PickCycle {
+        position: screen_pos,
+        entities,
+        index,
+    };
+
+    if ctrl {
+        selection.toggle(picked);
+    } else if shift {
+        selection.set_primary(picked);
+    } else {
+        selection.select(picked);
+    }
+}
+
+/// How far (in screen pixels) the cursor has to move before a press becomes a box selection
+const MARQUEE_DRAG_THRESHOLD: f32 = 4.0;
+
+/// Color of the selection box
+const MARQUEE_COLOR: Color = Color::srgba(0.3, 0.6, 1.0, 0.9);
+
+/// In-progress box selection in the viewport
+#[derive(Resource, Default)]
+pub struct MarqueeSelection {
+    /// World position where the box started (`None` when not box selecting)
+    pub start: Option<Vec2>,
+    /// World position of the opposite corner (follows the cursor)
+    pub end: Vec2,
+    /// Screen position where the box started, to tell a click from a drag
+    pub start_cursor: Vec2,
+    /// Whether the cursor moved far enough to count as a box selection
+    pub active: bool,
+}
+
+impl MarqueeSelection {
+    /// The selection box in world space, once it's a box selection
+    pub fn rect(&self) -> Option<Rect> {
+        let start = self.start.filter(|_| self.active)?;
+        Some(Rect::from_corners(start, self.end))
+    }
+}
+
+/// Start, track and apply box selections
+pub fn handle_marquee_selection(
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    cursor: ViewportCursor,
+    hover_map: Res<HoverMap>,
+    viewports: Query<&PointerId, With<EditorViewport>>,
+    drag_state: Res<GizmoDragState>,
+    mut marquee: ResMut<MarqueeSelection>,
+    mut selection: ResMut<EditorSelection>,
+    sprites: Query<(Entity, &Transform, &Sprite), Without<EditorEntity>>,
+) {
+    let cursor_pos = cursor.window_position();
+    let world_pos = cursor_world_position(&cursor);
+
+    if mouse_button.just_pressed(MouseButton::Left) {
+        // Only on empty space: not over a sprite or UI panel, and not grabbing a gizmo handle
+        if over_empty_viewport(&hover_map, &viewports)
+            && !drag_state.is_dragging
+            && let (Some(cursor_pos), Some(world_pos)) = (cursor_pos, world_pos)
+        {
+            *marquee = MarqueeSelection {
+                start: Some(world_pos),
+                end: world_pos,
+                start_cursor: cursor_pos,
+                active: false,
+            };
+        }
+    }
+
+    if marquee.start.is_none() {
+        return;
+    }
+
+    if let (Some(cursor_pos), Some(world_pos)) = (cursor_pos, world_pos) {
+        marquee.end = world_pos;
+        if cursor_pos.distance(marquee.start_cursor) > MARQUEE_DRAG_THRESHOLD {
+            marquee.active = true;
+        }
+    }
+
+    if mouse_button.pressed(MouseButton::Left) {
+        return;
+    }
+
+    // Released: apply the box (a plain click leaves the selection alone)
+    if let Some(rect) = marquee.rect() {
+        let hits: Vec<Entity> = sprites
+            .iter()
+            .filter(|(_, transform, sprite)| !sprite_bounds(transform, Some(sprite)).intersect(rect).is_empty())
+            .map(|(entity, _, _)| entity)
+            .collect();
+
+        let shift = keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+        let ctrl = keyboard.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+
+        if ctrl {
+            for entity in hits {
+                selection.remove(entity);
+            }
+        } else {
+            if !shift {
+                selection.clear();
+            }
+            for entity in hits {
+                selection.add(entity);
+            }
+        }
+    }
+
+    *marquee = MarqueeSelection::default();
+}
+
+/// Draw the selection box while box selecting
+pub fn draw_marquee(marquee: Res<MarqueeSelection>, mut gizmos: Gizmos) {
+    if let Some(rect) = marquee.rect() {
+        gizmos.rect_2d(rect.center(), rect.size(), MARQUEE_COLOR);
+    }
+}
@@ -0,0 +1,394 @@
+//! Offscreen viewport rendering
+//!
+//! Every `EditorViewport` node gets its own render target, 2D and 3D editor cameras and
+//! grid (`setup_viewports`), shown by a `ViewportNode` for the camera of the current mode.
+//! A separate camera draws the editor UI to the window. Add a `GridConfig` to a viewport
+//! node to override the grid in that viewport.
+//!
+//! Input goes to the hovered viewport (`ActiveViewport`): cursor positions are remapped
+//! from the window into its node, and picking reaches the scene through the node's own
+//! pointer.
+//!
+//! Editor gizmos draw on `EDITOR_OVERLAY_LAYER`, which only the editor cameras see. A
+//! `GameViewport` node previews the scene's own camera without them.
+
+use bevy::camera::RenderTarget;
+use bevy::camera::visibility::RenderLayers;
+use bevy::ecs::system::SystemParam;
+use bevy::image::BevyDefault;
+use bevy::picking::hover::HoverMap;
+use bevy::picking::pointer::PointerId;
+use bevy::prelude::*;
+use bevy::render::render_resource::TextureFormat;
+use bevy::ui::widget::ViewportNode;
+use bevy::ui::UiGlobalTransform;
+use bevy_editor_core::EditorEntity;
+use crate::{GridConfig, ViewportMode, spawn_editor_camera, spawn_editor_camera_3d};
+
+/// Render layer of the editor's gizmos (selection, handles, guides), hidden from the game
+pub const EDITOR_OVERLAY_LAYER: usize = 1;
+
+/// Marker for a UI node the viewport is displayed in
+///
+/// The layout spawns one and the toolbar adds more. `setup_viewports` gives each its
+/// cameras and attaches a `ViewportNode` for the active one.
+#[derive(Component)]
+pub struct EditorViewport;
+
+/// Marker for a UI node showing what the scene's camera sees (the game, without gizmos)
+#[derive(Component)]
+pub struct GameViewport;
+
+/// The camera rendering a `GameViewport` and the scene camera it copies (despawned with it)
+#[derive(Component, Debug, Clone, Copy)]
+pub struct GameViewportCamera {
+    pub camera: Entity,
+    pub source: Entity,
+}
+
+/// Marker for the editor camera that renders a `GameViewport`
+#[derive(Component)]
+pub struct GamePreviewCamera;
+
+/// The cameras and grid belonging to an `EditorViewport` (despawned with it)
+#[derive(Component, Debug, Clone)]
+pub struct ViewportCameras {
+    pub camera_2d: Entity,
+    pub camera_3d: Entity,
+    /// Retained gizmo the grid is drawn into, on a render layer only this viewport sees
+    pub grid: Entity,
+    pub grid_gizmo: Handle<GizmoAsset>,
+    /// The grid's render layer, handed back to `ViewportLayers` when the viewport goes away
+    pub grid_layer: usize,
+}
+
+impl ViewportCameras {
+    /// The camera used in the given mode
+    pub fn camera(&self, mode: ViewportMode) -> Entity {
+        match mode {
+            ViewportMode::TwoD => self.camera_2d,
+            ViewportMode::ThreeD => self.camera_3d,
+        }
+    }
+}
+
+/// Render layers for viewport grids, reused once the viewport that had one is gone
+#[derive(Resource, Debug, Default)]
+pub struct ViewportLayers {
+    free: Vec<usize>,
+    next: usize,
+}
+
+impl ViewportLayers {
+    /// A layer no live viewport uses (layer 0 is the scene, then the overlay, then the grids)
+    fn take(&mut self) -> usize {
+        self.free.pop().unwrap_or_else(|| {
+            self.next += 1;
+            EDITOR_OVERLAY_LAYER + self.next
+        })
+    }
+
+    fn release(&mut self, layer: usize) {
+        self.free.push(layer);
+    }
+}
+
+/// The viewport input goes to
+#[derive(Resource, Debug, Default, PartialEq, Eq)]
+pub struct ActiveViewport {
+    /// Last viewport the cursor was over (framing and gizmo drawing use it)
+    pub viewport: Option<Entity>,
+    /// Whether the cursor is over it (or was when the held mouse button was pressed)
+    pub hovered: bool,
+}
+
+impl ActiveViewport {
+    /// Camera of the active viewport for the given mode
+    pub fn camera(&self, viewports: &Query<&ViewportCameras>, mode: ViewportMode) -> Option<Entity> {
+        let cameras = viewports.get(self.viewport?).ok()?;
+        Some(cameras.camera(mode))
+    }
+
+    /// Camera of the viewport under the cursor, the only one mouse input should move
+    pub fn hovered_camera(&self, viewports: &Query<&ViewportCameras>, mode: ViewportMode) -> Option<Entity> {
+        self.camera(viewports, mode).filter(|_| self.hovered)
+    }
+}
+
+/// Give new viewport nodes a render target, cameras and grid
+pub(crate) fn setup_viewports(
+    viewports: Query<Entity, (With<EditorViewport>, Without<ViewportCameras>)>,
+    mode: Res<ViewportMode>,
+    mut images: ResMut<Assets<Image>>,
+    mut gizmo_assets: ResMut<Assets<GizmoAsset>>,
+    mut layers: ResMut<ViewportLayers>,
+    mut commands: Commands,
+) {
+    for viewport in &viewports {
+        // Sized to the node by bevy_ui once it's laid out
+        let image = Image::new_target_texture(1, 1, TextureFormat::bevy_default(), None);
+        let target = RenderTarget::from(images.add(image));
+
+        // Layer 0 is the scene, each viewport gets one more (after the overlay) for its own grid
+        let grid_layer = layers.take();
+        let camera_layers = RenderLayers::from_layers(&[0, EDITOR_OVERLAY_LAYER, grid_layer]);
+
+        let camera_2d = spawn_editor_camera(&mut commands, target.clone(), camera_layers.clone(), *mode == ViewportMode::TwoD);
+        let camera_3d = spawn_editor_camera_3d(&mut commands, target, camera_layers, *mode == ViewportMode::ThreeD);
+
+        let grid_gizmo = gizmo_assets.add(GizmoAsset::new());
+        let grid = commands.spawn((
+            Gizmo {
+                handle: grid_gizmo.clone(),
+                ..default()
+            },
+            RenderLayers::layer(grid_layer),
+            EditorEntity,
+            Name::new("Viewport Grid"),
+        )).id();
+
+        commands.entity(viewport).insert(ViewportCameras {
+            camera_2d,
+            camera_3d,
+            grid,
+            grid_gizmo,
+            grid_layer,
+        });
+    }
+}
+
+/// Despawn a viewport's cameras and grid along with it, freeing its grid layer
+pub(crate) fn despawn_viewport_cameras(
+    remove: On<Remove, ViewportCameras>,
+    viewports: Query<&ViewportCameras>,
+    mut layers: ResMut<ViewportLayers>,
+    mut commands: Commands,
+) {
+    if let Ok(cameras) = viewports.get(remove.entity) {
+        for entity in [cameras.camera_2d, cameras.camera_3d, cameras.grid] {
+            commands.entity(entity).try_despawn();
+        }
+        layers.release(cameras.grid_layer);
+    }
+}
+
+/// Draw the editor's immediate-mode gizmos on the overlay layer
+pub(crate) fn use_overlay_gizmo_layer(mut config_store: ResMut<GizmoConfigStore>) {
+    let (config, _) = config_store.config_mut::<DefaultGizmoConfigGroup>();
+    config.render_layers = RenderLayers::layer(EDITOR_OVERLAY_LAYER);
+}
+
+/// Point every `GameViewport` at the scene's camera, respawning its preview camera when
+/// that camera changes (a new scene, entering or leaving play mode)
+pub(crate) fn setup_game_viewports(
+    viewports: Query<(Entity, Option<&GameViewportCamera>), With<GameViewport>>,
+    scene_cameras: Query<(Entity, &Camera, Has<Camera2d>), (Without<EditorEntity>, Without<GamePreviewCamera>)>,
+    mut images: ResMut<Assets<Image>>,
+    mut commands: Commands,
+) {
+    // The active camera rendering first, as the game would show it
+    let source = scene_cameras
+        .iter()
+        .filter(|(_, camera, _)| camera.is_active)
+        .min_by_key(|(_, camera, _)| camera.order);
+
+    for (viewport, preview) in &viewports {
+        match source {
+            Some((source, _, _)) if preview.is_some_and(|preview| preview.source == source) => {}
+            Some((source, _, is_2d)) => {
+                let image = Image::new_target_texture(1, 1, TextureFormat::bevy_default(), None);
+                let target = RenderTarget::from(images.add(image));
+                let camera = spawn_game_preview_camera(&mut commands, target, is_2d);
+                commands
+                    .entity(viewport)
+                    .insert((GameViewportCamera { camera, source }, ViewportNode::new(camera)));
+            }
+            None if preview.is_some() => {
+                commands.entity(viewport).remove::<(GameViewportCamera, ViewportNode)>();
+            }
+            None => {}
+        }
+    }
+}
+
+/// Spawn a camera of the same kind as the scene camera, seeing only the scene layer
+fn spawn_game_preview_camera(commands: &mut Commands, target: RenderTarget, is_2d: bool) -> Entity {
+    let components = (
+        target,
+        RenderLayers::layer(0),
+        GamePreviewCamera,
+        EditorEntity,
+        Name::new("Game Preview Camera"),
+    );
+    if is_2d {
+        commands.spawn((Camera2d, components)).id()
+    } else {
+        commands.spawn((Camera3d::default(), components)).id()
+    }
+}
+
+/// Despawn a game viewport's preview camera when it's replaced or removed
+pub(crate) fn despawn_game_preview_camera(
+    replace: On<Replace, GameViewportCamera>,
+    viewports: Query<&GameViewportCamera>,
+    mut commands: Commands,
+) {
+    if let Ok(preview) = viewports.get(replace.entity) {
+        commands.entity(preview.camera).try_despawn();
+    }
+}
+
+/// Keep preview cameras where their scene camera is, looking the same way
+///
+/// Only copies what changed on the scene camera (or everything into a new preview camera),
+/// so an idle preview isn't marked changed every frame.
+pub(crate) fn sync_game_preview_cameras(
+    viewports: Query<&GameViewportCamera>,
+    sources: Query<(Ref<GlobalTransform>, Ref<Projection>, Ref<Camera>), Without<GamePreviewCamera>>,
+    mut previews: Query<(&mut Transform, &mut Projection, &mut Camera), With<GamePreviewCamera>>,
+) {
+    for preview in &viewports {
+        let Ok((source_transform, source_projection, source_camera)) = sources.get(preview.source) else {
+            continue;
+        };
+        let Ok((mut transform, mut projection, mut camera)) = previews.get_mut(preview.camera) else {
+            continue;
+        };
+        let new = transform.is_added();
+
+        if new || source_transform.is_changed() {
+            transform.set_if_neq(source_transform.compute_transform());
+        }
+        if new || source_projection.is_changed() {
+            *projection = source_projection.clone();
+        }
+        if new || source_camera.is_changed() {
+            camera.clear_color = source_camera.clear_color;
+        }
+    }
+}
+
+/// Track the viewport under the cursor (held while a mouse button is, so drags stay in
+/// the viewport they started in)
+pub fn update_active_viewport(
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    hover_map: Res<HoverMap>,
+    viewports: Query<Entity, With<EditorViewport>>,
+    mut active: ResMut<ActiveViewport>,
+) {
+    // Buttons pressed before this frame
+    let held = mouse_button.get_pressed().any(|button| !mouse_button.just_pressed(*button));
+    if active.hovered && held && active.viewport.is_some_and(|viewport| viewports.contains(viewport)) {
+        return;
+    }
+
+    let hovered = hover_map
+        .get(&PointerId::Mouse)
+        .and_then(|hits| hits.keys().copied().find(|entity| viewports.contains(*entity)));
+
+    // Keep the last hovered viewport until another one is hovered (or it's gone)
+    let viewport = hovered
+        .or(active.viewport.filter(|viewport| viewports.contains(*viewport)))
+        .or_else(|| viewports.iter().next());
+
+    active.set_if_neq(ActiveViewport {
+        viewport,
+        // A press that started outside the viewports doesn't become a viewport drag
+        hovered: hovered.is_some() && !held,
+    });
+}
+
+/// Show the camera matching the viewport mode in every viewport node
+pub fn attach_viewport_camera(
+    viewport_mode: Res<ViewportMode>,
+    viewports: Query<(Entity, &ViewportCameras, Option<&ViewportNode>)>,
+    mut commands: Commands,
+) {
+    for (entity, cameras, node) in &viewports {
+        let camera = cameras.camera(*viewport_mode);
+        if node.is_none_or(|node| node.camera != camera) {
+            commands.entity(entity).insert(ViewportNode::new(camera));
+        }
+    }
+}
+
+/// Logical-pixel rectangle of a UI node in the window
+fn node_rect(node: &ComputedNode, transform: &UiGlobalTransform) -> Rect {
+    let scale = node.inverse_scale_factor();
+    Rect::from_center_size(transform.translation * scale, node.size() * scale)
+}
+
+/// The active viewport's cameras, grid and cursor position
+#[derive(SystemParam)]
+pub struct ViewportCursor<'w, 's> {
+    active: Res<'w, ActiveViewport>,
+    windows: Query<'w, 's, &'static Window>,
+    viewports: Query<
+        'w,
+        's,
+        (&'static ComputedNode, &'static UiGlobalTransform, &'static ViewportCameras, Option<&'static GridConfig>),
+        With<EditorViewport>,
+    >,
+    cameras: Query<'w, 's, (&'static Camera, &'static GlobalTransform)>,
+}
+
+impl ViewportCursor<'_, '_> {
+    /// Cursor position in window (logical) coordinates, for placing UI next to it
+    pub fn window_position(&self) -> Option<Vec2> {
+        self.windows.single().ok()?.cursor_position()
+    }
+
+    /// Cursor position relative to the active viewport node, scaled to `camera`'s viewport
+    /// (`None` while the cursor is outside it)
+    pub fn position(&self, camera: &Camera) -> Option<Vec2> {
+        let cursor = self.window_position()?;
+        let viewport_size = camera.logical_viewport_size()?;
+        let (node, transform, _, _) = self.viewports.get(self.active.viewport?).ok()?;
+
+        let rect = node_rect(node, transform);
+        (rect.contains(cursor) && rect.width() > 0.0 && rect.height() > 0.0)
+            .then(|| (cursor - rect.min) / rect.size() * viewport_size)
+    }
+
+    fn camera(&self, mode: ViewportMode) -> Option<(&Camera, &GlobalTransform)> {
+        let (_, _, cameras, _) = self.viewports.get(self.active.viewport?).ok()?;
+        self.cameras.get(cameras.camera(mode)).ok()
+    }
+
+    /// The active viewport's 2D camera
+    pub fn camera_2d(&self) -> Option<(&Camera, &GlobalTransform)> {
+        self.camera(ViewportMode::TwoD)
+    }
+
+    /// The active viewport's 3D camera
+    pub fn camera_3d(&self) -> Option<(&Camera, &GlobalTransform)> {
+        self.camera(ViewportMode::ThreeD)
+    }
+
+    /// The active viewport's grid: its override, or `default`
+    pub fn grid<'a>(&'a self, default: &'a GridConfig) -> &'a GridConfig {
+        self.active
+            .viewport
+            .and_then(|viewport| self.viewports.get(viewport).ok())
+            .and_then(|(_, _, _, grid)| grid)
+            .unwrap_or(default)
+    }
+}
+
+/// Whether the mouse is over a viewport with nothing pickable under it (empty space)
+pub fn over_empty_viewport(
+    hover_map: &HoverMap,
+    viewport_pointers: &Query<&PointerId, With<EditorViewport>>,
+) -> bool {
+    let Some(hits) = hover_map.get(&PointerId::Mouse) else {
+        return false;
+    };
+
+    !hits.is_empty()
+        && hits.keys().all(|entity| {
+            // Anything but a viewport (a panel, a button) isn't empty space
+            viewport_pointers.get(*entity).is_ok_and(|pointer| {
+                hover_map.get(pointer).is_none_or(|scene_hits| scene_hits.is_empty())
+            })
+        })
+}
@@ -0,0 +1,272 @@
+//! Snapping for gizmo drags and inspector nudges
+//!
+//! Increments snap to the grid (or custom steps), and dragged sprites snap their edges and
+//! centers to other sprites, with smart guides showing the alignment.
+//! Increment snapping is toggled by `SnapPreferences::enabled` and sprite snapping by
+//! `SnapPreferences::to_sprites`; holding Ctrl inverts both while dragging.
+
+use bevy::prelude::*;
+use bevy_editor_core::SnapPreferences;
+use crate::GridConfig;
+
+/// Resolved snapping increments (all `None` when snapping is inactive)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SnapSteps {
+    /// Translation increment in world units
+    pub translate: Option<f32>,
+    /// Rotation increment in radians
+    pub rotate: Option<f32>,
+    /// Scale increment
+    pub scale: Option<f32>,
+    /// Distance in screen pixels within which sprite edges snap to other sprites
+    pub sprite_threshold_px: Option<f32>,
+}
+
+impl SnapSteps {
+    /// Increments from the preferences, translating by the grid cell size unless a custom step is set
+    pub fn from_preferences(preferences: &SnapPreferences, grid: &GridConfig) -> Self {
+        Self {
+            translate: Some(preferences.translate_step.unwrap_or(grid.cell_size)),
+            rotate: Some(preferences.rotate_degrees.to_radians()),
+            scale: Some(preferences.scale_step),
+            sprite_threshold_px: preferences.to_sprites.then_some(preferences.sprite_threshold_px),
+        }
+    }
+
+    /// Snapping to use right now: increments while snapping is enabled and sprite edges while
+    /// sprite snapping is, each toggle inverted by holding Ctrl
+    pub fn active(
+        preferences: &SnapPreferences,
+        grid: &GridConfig,
+        keyboard: &ButtonInput<KeyCode>,
+    ) -> Self {
+        let ctrl = keyboard.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+        let steps = Self::from_preferences(preferences, grid);
+        let increments = preferences.enabled != ctrl;
+        Self {
+            translate: steps.translate.filter(|_| increments),
+            rotate: steps.rotate.filter(|_| increments),
+            scale: steps.scale.filter(|_| increments),
+            sprite_threshold_px: (preferences.to_sprites != ctrl).then_some(preferences.sprite_threshold_px),
+        }
+    }
+
+    /// Whether values snap to increments
+    pub fn snaps_increments(&self) -> bool {
+        self.translate.is_some() || self.rotate.is_some() || self.scale.is_some()
+    }
+
+    /// Whether any snapping applies
+    pub fn is_active(&self) -> bool {
+        self.snaps_increments() || self.sprite_threshold_px.is_some()
+    }
+}
+
+/// Round a value to the nearest multiple of `step` (unchanged for a missing or non-positive step)
+pub fn snap_value(value: f32, step: Option<f32>) -> f32 {
+    match step {
+        Some(step) if step > f32::EPSILON => (value / step).round() * step,
+        _ => value,
+    }
+}
+
+/// Fallback size for entities without a sprite size
+const DEFAULT_SPRITE_SIZE: Vec2 = Vec2::new(64.0, 64.0);
+
+/// Color of the smart guides
+const GUIDE_COLOR: Color = Color::srgb(1.0, 0.2, 0.8);
+
+/// Unscaled size of a sprite (its custom size, or a default when it has none)
+pub fn sprite_size(sprite: Option<&Sprite>) -> Vec2 {
+    sprite
+        .and_then(|sprite| sprite.custom_size)
+        .unwrap_or(DEFAULT_SPRITE_SIZE)
+}
+
+/// World-space bounding box of a sprite, accounting for its rotation and scale
+pub fn sprite_bounds(transform: &Transform, sprite: Option<&Sprite>) -> Rect {
+    let half_size = sprite_size(sprite) * transform.scale.truncate() / 2.0;
+    let rotation = Rot2::radians(transform.rotation.to_euler(bevy::math::EulerRot::XYZ).2);
+
+    // Extent of the rotated rectangle along each world axis
+    let extent = Vec2::new(
+        (rotation.cos * half_size.x).abs() + (rotation.sin * half_size.y).abs(),
+        (rotation.sin * half_size.x).abs() + (rotation.cos * half_size.y).abs(),
+    );
+    Rect::from_center_half_size(transform.translation.truncate(), extent)
+}
+
+/// Alignment lines shown while a dragged sprite is snapped to other sprites
+#[derive(Resource, Default)]
+pub struct SmartGuides {
+    /// Guide segments in world space
+    pub lines: Vec<(Vec2, Vec2)>,
+}
+
+/// Result of snapping bounds to other sprites
+#[derive(Debug, Default)]
+pub struct SpriteSnap {
+    /// Offset to add to the dragged entities
+    pub offset: Vec2,
+    /// Guides for every alignment after applying the offset
+    pub guides: Vec<(Vec2, Vec2)>,
+}
+
+/// Snap the edges and center of `moving` to the edges and centers of `others`
+///
+/// Each axis snaps independently to the closest candidate within `threshold` (world units).
+/// `snap_x`/`snap_y` restrict which axes may move (e.g. while dragging a single axis handle).
+pub fn snap_bounds_to_sprites(
+    moving: Rect,
+    others: &[Rect],
+    threshold: f32,
+    snap_x: bool,
+    snap_y: bool,
+) -> SpriteSnap {
+    let xs = |rect: Rect| [rect.min.x, rect.center().x, rect.max.x];
+    let ys = |rect: Rect| [rect.min.y, rect.center().y, rect.max.y];
+
+    // Smallest offset that lines up one of the moving values with one of the other values
+    let closest = |moving: [f32; 3], others: &mut dyn Iterator<Item = [f32; 3]>| {
+        let mut best: Option<f32> = None;
+        for other in others {
+            for from in moving {
+                for to in other {
+                    let offset = to - from;
+                    if offset.abs() <= threshold && best.is_none_or(|best| offset.abs() < best.abs()) {
+                        best = Some(offset);
+                    }
+                }
+            }
+        }
+        best
+    };
+
+    let offset = Vec2::new(
+        if snap_x { closest(xs(moving), &mut others.iter().map(|rect| xs(*rect))) } else { None }.unwrap_or(0.0),
+        if snap_y { closest(ys(moving), &mut others.iter().map(|rect| ys(*rect))) } else { None }.unwrap_or(0.0),
+    );
+
+    // Guides for everything that lines up once snapped
+    let snapped = Rect::from_corners(moving.min + offset, moving.max + offset);
+    let mut guides = Vec::new();
+    for other in others {
+        let span = snapped.union(*other);
+        for x in xs(snapped) {
+            if xs(*other).iter().any(|other_x| (other_x - x).abs() < 0.01) {
+                guides.push((Vec2::new(x, span.min.y), Vec2::new(x, span.max.y)));
+            }
+        }
+        for y in ys(snapped) {
+            if ys(*other).iter().any(|other_y| (other_y - y).abs() < 0.01) {
+                guides.push((Vec2::new(span.min.x, y), Vec2::new(span.max.x, y)));
+            }
+        }
+    }
+
+    SpriteSnap { offset, guides }
+}
+
+/// Draw the current smart guides
+pub fn draw_smart_guides(guides: Res<SmartGuides>, mut gizmos: Gizmos) {
+    for (start, end) in &guides.lines {
+        gizmos.line_2d(*start, *end, GUIDE_COLOR);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snap_value_rounds_to_step() {
+        assert_eq!(snap_value(12.0, Some(5.0)), 10.0);
+        assert_eq!(snap_value(13.0, Some(5.0)), 15.0);
+        assert_eq!(snap_value(-7.6, Some(2.5)), -7.5);
+        // Missing or unusable steps leave the value alone
+        assert_eq!(snap_value(12.3, None), 12.3);
+        assert_eq!(snap_value(12.3, Some(0.0)), 12.3);
+        assert_eq!(snap_value(12.3, Some(-1.0)), 12.3);
+    }
+
+    #[test]
+    fn translate_step_defaults_to_grid_cell() {
+        let grid = GridConfig { cell_size: 32.0, ..default() };
+        let mut preferences = SnapPreferences::default();
+        assert_eq!(SnapSteps::from_preferences(&preferences, &grid).translate, Some(32.0));
+
+        preferences.translate_step = Some(4.0);
+        preferences.to_sprites = false;
+        let steps = SnapSteps::from_preferences(&preferences, &grid);
+        assert_eq!(steps.translate, Some(4.0));
+        assert_eq!(steps.sprite_threshold_px, None);
+    }
+
+    #[test]
+    fn ctrl_inverts_snapping() {
+        let grid = GridConfig::default();
+        let mut keyboard = ButtonInput::<KeyCode>::default();
+        let mut preferences = SnapPreferences { enabled: false, to_sprites: false, ..default() };
+
+        assert!(!SnapSteps::active(&preferences, &grid, &keyboard).is_active());
+        keyboard.press(KeyCode::ControlLeft);
+        assert!(SnapSteps::active(&preferences, &grid, &keyboard).snaps_increments());
+
+        preferences.enabled = true;
+        assert!(!SnapSteps::active(&preferences, &grid, &keyboard).snaps_increments());
+        keyboard.release(KeyCode::ControlLeft);
+        assert!(SnapSteps::active(&preferences, &grid, &keyboard).snaps_increments());
+    }
+
+    #[test]
+    fn rotated_sprite_bounds_swap_extents() {
+        let sprite = Sprite { custom_size: Some(Vec2::new(20.0, 10.0)), ..default() };
+        let transform =
+            Transform::from_xyz(5.0, 0.0, 0.0).with_rotation(Quat::from_rotation_z(std::f32::consts::FRAC_PI_2));
+
+        let bounds = sprite_bounds(&transform, Some(&sprite));
+        assert!(bounds.center().abs_diff_eq(Vec2::new(5.0, 0.0), 1e-4));
+        assert!(bounds.size().abs_diff_eq(Vec2::new(10.0, 20.0), 1e-4));
+    }
+
+    #[test]
+    fn bounds_snap_to_closest_edge() {
+        let moving = Rect::new(0.0, 0.0, 10.0, 10.0);
+        let other = Rect::new(13.0, 0.0, 23.0, 10.0);
+
+        let snap = snap_bounds_to_sprites(moving, &[other], 5.0, true, true);
+        // Right edge onto the other's left edge; already level vertically
+        assert_eq!(snap.offset, Vec2::new(3.0, 0.0));
+        // One vertical guide for the touching edges, three horizontal for bottom, center and top
+        assert_eq!(snap.guides.len(), 4);
+    }
+
+    #[test]
+    fn bounds_snap_respects_threshold_and_axes() {
+        let moving = Rect::new(0.0, 1.0, 10.0, 11.0);
+        let other = Rect::new(13.0, 0.0, 23.0, 10.0);
+
+        let snap = snap_bounds_to_sprites(moving, &[other], 2.0, true, true);
+        assert_eq!(snap.offset, Vec2::new(0.0, -1.0));
+
+        let snap = snap_bounds_to_sprites(moving, &[other], 5.0, false, true);
+        assert_eq!(snap.offset, Vec2::new(0.0, -1.0));
+    }
+
+    #[test]
+    fn sprite_snapping_has_its_own_toggle() {
+        let grid = GridConfig::default();
+        let mut keyboard = ButtonInput::<KeyCode>::default();
+        let preferences = SnapPreferences { enabled: false, to_sprites: true, ..default() };
+
+        let snap = SnapSteps::active(&preferences, &grid, &keyboard);
+        assert!(!snap.snaps_increments());
+        assert_eq!(snap.sprite_threshold_px, Some(preferences.sprite_threshold_px));
+
+        // Ctrl flips both toggles
+        keyboard.press(KeyCode::ControlLeft);
+        let snap = SnapSteps::active(&preferences, &grid, &keyboard);
+        assert!(snap.snaps_increments());
+        assert_eq!(snap.sprite_threshold_px, None);
+    }
+}